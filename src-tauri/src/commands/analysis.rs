@@ -0,0 +1,139 @@
+use crate::engine::analysis;
+use crate::models::{
+    DifficultyWeights, DistributionStats, DungeonLayout, GenerationResult, LayoutPosition,
+    Rectangle, SpawnPoint,
+};
+use std::collections::HashMap;
+use tauri::command;
+
+#[command]
+pub fn reachable_area_fraction(layout: DungeonLayout) -> Result<f64, String> {
+    Ok(analysis::reachable_area_fraction(&layout))
+}
+
+#[command]
+pub fn batch_diversity(results: Vec<GenerationResult>) -> Result<f64, String> {
+    let layouts: Vec<&DungeonLayout> = results.iter().filter_map(|r| r.data.as_ref()).collect();
+    Ok(analysis::batch_diversity(&layouts))
+}
+
+#[command]
+pub fn longest_dead_end(layout: DungeonLayout) -> Result<usize, String> {
+    Ok(analysis::longest_dead_end(&layout))
+}
+
+#[command]
+pub fn layout_mst(layout: DungeonLayout) -> Result<Vec<(String, String, f64)>, String> {
+    Ok(analysis::layout_mst(&layout))
+}
+
+#[command]
+pub fn overlapping_rooms(
+    layout: DungeonLayout,
+    tolerance: Option<f64>,
+) -> Result<Vec<(String, String)>, String> {
+    Ok(analysis::overlapping_rooms(
+        &layout,
+        tolerance.unwrap_or(0.0),
+    ))
+}
+
+#[command]
+pub fn spawns_by_type(layout: DungeonLayout) -> Result<HashMap<String, Vec<SpawnPoint>>, String> {
+    Ok(analysis::spawns_by_type(&layout))
+}
+
+#[command]
+pub fn door_counts(layout: DungeonLayout) -> Result<HashMap<String, u32>, String> {
+    Ok(analysis::door_counts(&layout))
+}
+
+#[command]
+pub fn room_size_by_type(layout: DungeonLayout) -> Result<HashMap<String, (f64, f64)>, String> {
+    Ok(analysis::room_size_by_type(&layout))
+}
+
+#[command]
+pub fn populated_fraction(layout: DungeonLayout) -> Result<f64, String> {
+    Ok(analysis::populated_fraction(&layout))
+}
+
+#[command]
+pub fn weighted_centroid(layout: DungeonLayout) -> Result<LayoutPosition, String> {
+    Ok(analysis::weighted_centroid(&layout))
+}
+
+#[command]
+pub fn crossing_connections(layout: DungeonLayout) -> Result<Vec<(String, String)>, String> {
+    Ok(analysis::crossing_connections(&layout))
+}
+
+#[command]
+pub fn backtrack_ratio(layout: DungeonLayout) -> Result<f64, String> {
+    Ok(analysis::backtrack_ratio(&layout))
+}
+
+/// Average number of rooms between the start room and each reachable room
+#[command]
+pub fn mean_depth(layout: DungeonLayout) -> Result<f64, String> {
+    Ok(analysis::mean_depth(&layout))
+}
+
+/// 1 for a straight chain of rooms, near 0 for a heavily branched layout
+#[command]
+pub fn linearity(layout: DungeonLayout) -> Result<f64, String> {
+    Ok(analysis::linearity(&layout))
+}
+
+/// Ids of rooms whose removal would split the dungeon apart
+#[command]
+pub fn chokepoints(layout: DungeonLayout) -> Result<Vec<String>, String> {
+    Ok(analysis::chokepoints(&layout))
+}
+
+/// Difficulty from 1 to 10; weights left out of the payload take their defaults
+#[command]
+pub fn difficulty_rating(layout: DungeonLayout, weights: DifficultyWeights) -> Result<u8, String> {
+    Ok(analysis::difficulty_rating(&layout, &weights))
+}
+
+#[command]
+pub fn tags_in_use(layout: DungeonLayout) -> Result<Vec<String>, String> {
+    Ok(analysis::tags_in_use(&layout))
+}
+
+#[command]
+pub fn camera_bounds(
+    layout: DungeonLayout,
+    margin: f64,
+) -> Result<HashMap<String, Rectangle>, String> {
+    Ok(analysis::camera_bounds(&layout, margin))
+}
+
+#[command]
+pub fn connection_length_stats(layout: DungeonLayout) -> Result<DistributionStats, String> {
+    Ok(analysis::connection_length_stats(&layout))
+}
+
+#[command]
+pub fn compactness(layout: DungeonLayout) -> Result<f64, String> {
+    Ok(analysis::compactness(&layout))
+}
+
+/// Tile cells across all rooms, rasterizing rooms without a tile grid
+#[command]
+pub fn total_tiles(layout: DungeonLayout) -> Result<u64, String> {
+    Ok(analysis::total_tiles(&layout))
+}
+
+/// Gini coefficient of loot per room: 0 is perfectly even, 1 is all loot in one room
+#[command]
+pub fn loot_distribution_evenness(layout: DungeonLayout) -> Result<f64, String> {
+    Ok(analysis::loot_distribution_evenness(&layout))
+}
+
+/// Room ids in depth-first order from the start room, for fly-through previews
+#[command]
+pub fn tour_order(layout: DungeonLayout) -> Result<Vec<String>, String> {
+    Ok(analysis::tour_order(&layout))
+}
@@ -1,15 +1,19 @@
-use crate::engine::GraphExecutor;
+use crate::engine::stats::calculate_stats;
+use crate::engine::{self, analysis, constraints, ExecutionContext, GraphExecutor};
+use crate::models::generator::{Generator, GeneratorType, GraphNode};
 use crate::models::{
-    ConstraintResult, ConstraintStats, DistributionStats, DungeonLayout, GeneratedRoom,
-    GenerationMetadata, GenerationRequest, GenerationResult, HistogramBucket, LayoutPosition,
-    Percentiles, Rectangle, RoomConnection, SimulationConfig, SimulationResults,
-    SimulationStatistics, SpawnPoint,
+    AdjacencyReport, ConstraintResult, ConstraintStats, DungeonLayout, GeneratedRoom,
+    GenerationMetadata, GenerationRequest, GenerationResult, GenerationSummary, LayoutPosition,
+    OutputDescriptor, Rectangle, RoomConnection, SelectionObjective, SimulationConfig,
+    SimulationProgress, SimulationResults, SimulationStatistics, SpawnPoint,
 };
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
-use std::collections::HashMap;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
-use tauri::command;
+use tauri::{command, AppHandle, Emitter, State};
 
 #[command]
 pub fn generate_once(request: GenerationRequest) -> Result<GenerationResult, String> {
@@ -22,64 +26,375 @@ pub fn generate_once(request: GenerationRequest) -> Result<GenerationResult, Str
         println!("[Rust] graph nodes: {}", gen.graph.nodes.len());
         println!("[Rust] graph edges: {}", gen.graph.edges.len());
     }
-    
+
     let start = Instant::now();
 
+    // Loot generators produce items only and never go through room layout
+    if let Some(generator) = request
+        .generator
+        .as_ref()
+        .filter(|g| matches!(g.generator_type, GeneratorType::Loot))
+    {
+        return Ok(generate_loot(&request, generator, start));
+    }
+
+    // Per-request severity overrides, e.g. downgrading errors while drafting
+    let mut request = request;
+    if let Some(generator) = request.generator.as_mut() {
+        constraints::apply_severity_overrides(&mut generator.constraints, &request.parameters);
+    }
+
     // If we have a generator with a graph, use the graph executor
-    let (result, node_executions) = if let Some(ref generator) = request.generator {
-        // Use graph-based generation
-        let mut executor = GraphExecutor::new(request.seed, request.parameters.clone());
-        match executor.execute(generator) {
-            Ok(layout) => (layout, executor.node_executions()),
-            Err(e) => {
-                // Fall back to simple generation on error
-                let mut rng = ChaCha8Rng::seed_from_u64(request.seed);
-                let layout = generate_dungeon(&mut rng);
-                return Ok(GenerationResult {
-                    seed: request.seed,
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs(),
-                    success: false,
-                    data: Some(layout),
-                    constraint_results: vec![],
-                    metadata: GenerationMetadata {
-                        node_executions: 0,
-                        retry_count: 0,
-                    },
-                    errors: vec![format!("Graph execution error: {}", e)],
-                    duration_ms: start.elapsed().as_millis() as u64,
-                });
+    let attempt = if let Some(ref generator) = request.generator {
+        let max_retries = max_retries(&request.parameters);
+
+        // Retry with derived seeds while Error-severity constraints fail
+        let mut best: Option<Attempt> = None;
+        for (retry, seed) in retry_seeds(request.seed)
+            .take(max_retries as usize + 1)
+            .enumerate()
+        {
+            let mut executor = GraphExecutor::new(seed, request.parameters.clone());
+            let layout = match executor.execute(generator) {
+                Ok(layout) => layout,
+                Err(e) if is_strict(&request) => {
+                    // Strict mode never substitutes the legacy generator's output
+                    return Ok(GenerationResult {
+                        seed,
+                        timestamp: unix_timestamp(),
+                        success: false,
+                        data: None,
+                        loot: None,
+                        constraint_results: vec![],
+                        metadata: GenerationMetadata {
+                            node_executions: executor.node_executions(),
+                            retry_count: retry as u32,
+                            rng_draws: 0,
+                        },
+                        errors: vec![format!("Graph execution error: {}", e)],
+                        duration_ms: start.elapsed().as_millis() as u64,
+                    });
+                }
+                Err(e) => {
+                    // Fall back to simple generation on error
+                    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                    let layout = generate_dungeon(&mut rng);
+                    return Ok(GenerationResult {
+                        seed,
+                        timestamp: unix_timestamp(),
+                        success: false,
+                        data: Some(layout),
+                        loot: None,
+                        constraint_results: vec![],
+                        metadata: GenerationMetadata {
+                            node_executions: executor.node_executions(),
+                            retry_count: retry as u32,
+                            rng_draws: 0,
+                        },
+                        errors: vec![format!("Graph execution error: {}", e)],
+                        duration_ms: start.elapsed().as_millis() as u64,
+                    });
+                }
+            };
+
+            let constraint_results = constraints::evaluate(generator, &layout);
+            let failures = constraint_results
+                .iter()
+                .filter(|r| constraints::is_error_failure(&generator.constraints, r))
+                .count();
+            let attempt = Attempt {
+                seed,
+                layout,
+                node_executions: executor.node_executions(),
+                rng_draws: executor.rng_draws(),
+                retry_count: retry as u32,
+                constraint_results,
+                failures,
+            };
+            if best.as_ref().is_none_or(|b| attempt.failures < b.failures) {
+                best = Some(attempt);
+            }
+            if failures == 0 {
+                break;
             }
         }
+        best.expect("at least one attempt always runs")
     } else {
         // Fall back to simple procedural generation
         let mut rng = ChaCha8Rng::seed_from_u64(request.seed);
-        (generate_dungeon(&mut rng), 10)
+        let layout = generate_dungeon(&mut rng);
+        Attempt {
+            seed: request.seed,
+            layout,
+            node_executions: 10,
+            rng_draws: rng.get_word_pos() as u64,
+            retry_count: 0,
+            constraint_results: vec![],
+            failures: 0,
+        }
     };
 
+    // Strict mode also rejects overlapping rooms, allowing touches within overlapTolerance
+    let mut errors = vec![];
+    if is_strict(&request) {
+        let tolerance = request
+            .parameters
+            .get("overlapTolerance")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0);
+        errors.extend(
+            analysis::overlapping_rooms(&attempt.layout, tolerance)
+                .into_iter()
+                .map(|(a, b)| format!("Rooms {} and {} overlap", a, b)),
+        );
+    }
+    if let Some(ref generator) = request.generator {
+        errors.extend(
+            attempt
+                .constraint_results
+                .iter()
+                .filter(|r| constraints::is_error_failure(&generator.constraints, r))
+                .map(|r| {
+                    format!(
+                        "Constraint {} failed: {}",
+                        r.constraint_id,
+                        r.message.as_deref().unwrap_or("no details")
+                    )
+                }),
+        );
+    }
+
     let duration = start.elapsed();
 
     Ok(GenerationResult {
+        seed: attempt.seed,
+        timestamp: unix_timestamp(),
+        success: errors.is_empty(),
+        data: Some(attempt.layout),
+        loot: None,
+        constraint_results: attempt.constraint_results,
+        metadata: GenerationMetadata {
+            node_executions: attempt.node_executions,
+            retry_count: attempt.retry_count,
+            rng_draws: attempt.rng_draws,
+        },
+        errors,
+        duration_ms: duration.as_millis() as u64,
+    })
+}
+
+/// Retries `generate_once` makes when `maxRetries` isn't given
+const DEFAULT_MAX_RETRIES: u64 = 5;
+
+/// Upper bound on `maxRetries`, so one request can't run the graph indefinitely
+const MAX_RETRIES_LIMIT: u64 = 100;
+
+/// `maxRetries` from the request parameters, clamped to `MAX_RETRIES_LIMIT`
+fn max_retries(parameters: &HashMap<String, serde_json::Value>) -> u64 {
+    parameters
+        .get("maxRetries")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_MAX_RETRIES)
+        .min(MAX_RETRIES_LIMIT)
+}
+
+/// ChaCha stream that retry seeds are drawn from, apart from the default stream
+const RETRY_STREAM: u64 = 1;
+
+/// Seeds for each retry of a request at `seed`; retry 0 is the seed itself.
+///
+/// Later retries draw from a stream derived from `seed` rather than counting
+/// up, so they never land on a neighbouring request's seed.
+fn retry_seeds(seed: u64) -> impl Iterator<Item = u64> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(RETRY_STREAM);
+    std::iter::once(seed).chain(std::iter::repeat_with(move || rng.next_u64()))
+}
+
+/// One graph run in `generate_once`'s retry loop
+struct Attempt {
+    seed: u64,
+    layout: DungeonLayout,
+    node_executions: u32,
+    rng_draws: u64,
+    retry_count: u32,
+    constraint_results: Vec<ConstraintResult>,
+    /// Failed Error-severity constraints; 0 means the run is accepted
+    failures: usize,
+}
+
+fn generate_loot(
+    request: &GenerationRequest,
+    generator: &Generator,
+    start: Instant,
+) -> GenerationResult {
+    let mut executor = GraphExecutor::new(request.seed, request.parameters.clone());
+    let (loot, errors) = match executor.execute_loot(generator) {
+        Ok(loot) => (Some(loot), vec![]),
+        Err(e) => (None, vec![format!("Graph execution error: {}", e)]),
+    };
+
+    GenerationResult {
         seed: request.seed,
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs(),
+        timestamp: unix_timestamp(),
+        success: errors.is_empty(),
+        data: None,
+        loot,
+        constraint_results: vec![],
+        metadata: GenerationMetadata {
+            node_executions: executor.node_executions(),
+            retry_count: 0,
+            rng_draws: executor.rng_draws(),
+        },
+        errors,
+        duration_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+/// Strict requests report graph failures as-is instead of falling back
+fn is_strict(request: &GenerationRequest) -> bool {
+    request
+        .parameters
+        .get("strict")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Regenerate with a new seed while keeping the given rooms from a prior result fixed
+#[command]
+pub fn regenerate_with_locks(
+    generator: Generator,
+    prior_result: GenerationResult,
+    locked_room_ids: Vec<String>,
+    seed: u64,
+    parameters: Option<HashMap<String, serde_json::Value>>,
+) -> Result<GenerationResult, String> {
+    let start = Instant::now();
+    let prior = prior_result
+        .data
+        .as_ref()
+        .ok_or("Prior result has no layout to lock rooms from")?;
+
+    let (layout, node_executions) = engine::regenerate_with_locks(
+        &generator,
+        prior,
+        &locked_room_ids,
+        seed,
+        parameters.unwrap_or_default(),
+    )
+    .map_err(|e| format!("Graph execution error: {}", e))?;
+
+    Ok(GenerationResult {
+        seed,
+        timestamp: unix_timestamp(),
         success: true,
-        data: Some(result),
-        constraint_results: vec![ConstraintResult {
-            constraint_id: "connected".to_string(),
-            passed: true,
-            message: Some("All rooms reachable".to_string()),
-        }],
+        data: Some(layout),
+        loot: None,
+        constraint_results: vec![],
         metadata: GenerationMetadata {
             node_executions,
             retry_count: 0,
+            rng_draws: 0,
         },
         errors: vec![],
-        duration_ms: duration.as_millis() as u64,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Run one node against a caller-supplied context and return the mutated context
+#[command]
+pub fn preview_node(
+    node: GraphNode,
+    seed: u64,
+    context: ExecutionContext,
+) -> Result<ExecutionContext, String> {
+    let mut context = context;
+    GraphExecutor::new(seed, HashMap::new())
+        .preview_node(&node, &mut context)
+        .map_err(|e| format!("Failed to preview node: {}", e))?;
+    Ok(context)
+}
+
+/// Generate `count` full results, where item `i` is exactly `generate_once` at `request.seed + i`.
+///
+/// Constraint retries inside an item draw their own derived seeds (see
+/// `retry_seeds`), so they never shift the seeds of later items.
+#[command]
+pub fn generate_batch(
+    request: GenerationRequest,
+    count: u32,
+) -> Result<Vec<GenerationResult>, String> {
+    (0..count as u64)
+        .map(|offset| {
+            let mut run = request.clone();
+            run.seed = request.seed.wrapping_add(offset);
+            generate_once(run)
+        })
+        .collect()
+}
+
+/// Cells per side of the density grid in gallery summaries
+const GALLERY_GRID_SIZE: usize = 8;
+
+/// Generate `count` consecutive seeds starting at `request.seed` and return
+/// coarse previews instead of full geometry
+#[command]
+pub fn seed_gallery(
+    request: GenerationRequest,
+    count: u32,
+) -> Result<Vec<GenerationSummary>, String> {
+    (0..count as u64)
+        .map(|offset| {
+            let mut run = request.clone();
+            run.seed = request.seed.wrapping_add(offset);
+            let result = generate_once(run)?;
+            let layout = result.data.as_ref();
+            Ok(GenerationSummary {
+                seed: result.seed,
+                success: result.success,
+                room_count: layout.map_or(0, |l| l.rooms.len()),
+                bounds: layout.and_then(analysis::layout_bounds),
+                density: layout
+                    .map(|l| analysis::density_grid(l, GALLERY_GRID_SIZE))
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+#[command]
+pub fn output_descriptor(generator: Generator) -> Result<OutputDescriptor, String> {
+    let (result_type, spatial, note) = match generator.generator_type {
+        GeneratorType::Dungeon => ("dungeonLayout", true, None),
+        GeneratorType::Loot => (
+            "lootList",
+            false,
+            Some("Loot generators produce a list of items rather than a spatial layout"),
+        ),
+        GeneratorType::Encounter => (
+            "encounterList",
+            false,
+            Some("Encounter generators produce a list of entities rather than a spatial layout"),
+        ),
+        GeneratorType::Custom => (
+            "custom",
+            true,
+            Some("Custom generators return a dungeon layout shaped by their output schema"),
+        ),
+    };
+
+    Ok(OutputDescriptor {
+        result_type: result_type.to_string(),
+        spatial,
+        schema: generator.output_schema,
+        notes: note.map(|n| vec![n.to_string()]).unwrap_or_default(),
     })
 }
 
@@ -184,126 +499,976 @@ fn generate_dungeon(rng: &mut ChaCha8Rng) -> DungeonLayout {
         spawn_points,
         player_start,
         exits: vec![exit],
+        metadata: HashMap::new(),
     }
 }
 
-#[command]
-pub fn run_simulation(config: SimulationConfig) -> Result<SimulationResults, String> {
-    let start = Instant::now();
-    let mut room_counts: Vec<f64> = Vec::new();
-    let mut path_lengths: Vec<f64> = Vec::new();
-    let mut enemy_counts: Vec<f64> = Vec::new();
-    let mut item_counts: Vec<f64> = Vec::new();
-    let mut successes = 0u32;
+/// Managed state shared by `run_simulation` and `cancel_simulation`
+#[derive(Default)]
+pub struct SimulationState {
+    cancelled: AtomicBool,
+}
 
-    let seed_start = config.seed_start.unwrap_or(0);
+/// Runs off the main thread so `cancel_simulation` can be handled while it loops.
+///
+/// Emits `simulation-progress` after every chunk of runs.
+#[command(async)]
+pub fn run_simulation(
+    app: AppHandle,
+    config: SimulationConfig,
+    state: State<'_, SimulationState>,
+) -> Result<SimulationResults, String> {
+    state.cancelled.store(false, Ordering::SeqCst);
+    simulate(&config, &state.cancelled, |progress| {
+        let _ = app.emit("simulation-progress", progress);
+    })
+}
 
-    for i in 0..config.run_count {
-        let mut rng = ChaCha8Rng::seed_from_u64(seed_start + i as u64);
-        let layout = generate_dungeon(&mut rng);
+/// Simulate `config` and compare how often room types are connected against their base rates
+#[command(async)]
+pub fn adjacency_report(config: SimulationConfig) -> Result<AdjacencyReport, String> {
+    let results = simulate(&config, &AtomicBool::new(false), |_| {})?;
+    Ok(AdjacencyReport {
+        runs: results.runs,
+        total_transitions: results
+            .transition_matrix
+            .values()
+            .flat_map(|row| row.values())
+            .sum(),
+        pairs: analysis::adjacency_lift(&results.transition_matrix),
+    })
+}
+
+/// Runs generated in parallel between cancellation checks, aggregation and progress reports
+const SIMULATION_CHUNK: u32 = 256;
 
-        room_counts.push(layout.rooms.len() as f64);
-        path_lengths.push(layout.connections.len() as f64 + 1.0);
-        enemy_counts.push(layout.spawn_points.len() as f64);
-        item_counts.push(0.0); // Placeholder
-        successes += 1;
+/// Run `config.run_count` simulated layouts, stopping early once `cancelled` is set.
+///
+/// Runs are generated in parallel chunks but folded in seed order, so the
+/// statistics match a serial run exactly. `on_progress` hears after each chunk,
+/// which keeps large simulations to one report per `SIMULATION_CHUNK` runs. A
+/// cancelled simulation reports the runs completed so far plus a warning.
+fn simulate(
+    config: &SimulationConfig,
+    cancelled: &AtomicBool,
+    mut on_progress: impl FnMut(SimulationProgress),
+) -> Result<SimulationResults, String> {
+    let mut config = config.clone();
+    constraints::apply_severity_overrides(&mut config.constraints, &config.parameters);
+    let config = &config;
+    let start = Instant::now();
+    let seed_start = config.seed_start.unwrap_or(0);
+    let mut tally = SimulationTally::default();
+
+    let mut chunk_start = 0;
+    while chunk_start < config.run_count {
+        let chunk_end = chunk_start
+            .saturating_add(SIMULATION_CHUNK)
+            .min(config.run_count);
+        // `None` marks runs skipped because the flag was set mid-chunk
+        let outcomes: Vec<Option<Result<RunMetrics, String>>> = (chunk_start..chunk_end)
+            .into_par_iter()
+            .map(|i| {
+                (!cancelled.load(Ordering::SeqCst))
+                    .then(|| simulate_run(config, seed_start + i as u64))
+            })
+            .collect();
+        for outcome in outcomes.into_iter().flatten() {
+            tally.add(outcome);
+        }
+        on_progress(SimulationProgress {
+            completed: tally.runs,
+            total: config.run_count,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        });
+        if cancelled.load(Ordering::SeqCst) {
+            tally.warnings.push(format!(
+                "Simulation cancelled after {} of {} runs",
+                tally.runs, config.run_count
+            ));
+            break;
+        }
+        chunk_start = chunk_end;
     }
 
-    let duration = start.elapsed();
+    Ok(tally.finish(config, start))
+}
 
-    Ok(SimulationResults {
-        config: config.clone(),
-        runs: config.run_count,
-        success_rate: successes as f64 / config.run_count as f64,
-        duration_ms: duration.as_millis() as u64,
-        statistics: SimulationStatistics {
-            room_count: calculate_stats(&room_counts),
-            path_length: calculate_stats(&path_lengths),
-            enemy_count: calculate_stats(&enemy_counts),
-            item_count: calculate_stats(&item_counts),
-        },
-        constraint_results: HashMap::from([(
-            "connected".to_string(),
-            ConstraintStats {
-                pass_rate: 1.0,
-                violations: 0,
-            },
-        )]),
-        warnings: vec![],
+/// What one simulated run contributes to the aggregate results
+struct RunMetrics {
+    room_count: f64,
+    path_length: f64,
+    enemy_count: f64,
+    item_count: f64,
+    transitions: HashMap<String, HashMap<String, u32>>,
+    constraint_results: Vec<ConstraintResult>,
+    success: bool,
+}
+
+fn simulate_run(config: &SimulationConfig, seed: u64) -> Result<RunMetrics, String> {
+    let layout = simulate_layout(config, seed)?;
+    let mut transitions = HashMap::new();
+    analysis::count_transitions(&layout, &mut transitions);
+    let constraint_results =
+        constraints::evaluate_constraints(&config.constraints, &layout, config.short_circuit);
+
+    Ok(RunMetrics {
+        room_count: layout.rooms.len() as f64,
+        path_length: layout.connections.len() as f64 + 1.0,
+        enemy_count: layout.spawn_points.len() as f64,
+        item_count: layout.rooms.iter().map(|r| r.entities.len()).sum::<usize>() as f64,
+        transitions,
+        success: !constraints::has_error_failure(&config.constraints, &constraint_results),
+        constraint_results,
     })
 }
 
-fn calculate_stats(data: &[f64]) -> DistributionStats {
-    if data.is_empty() {
-        return DistributionStats {
-            min: 0.0,
-            max: 0.0,
-            mean: 0.0,
-            median: 0.0,
-            std_dev: 0.0,
-            percentiles: Percentiles {
-                p5: 0.0,
-                p25: 0.0,
-                p75: 0.0,
-                p95: 0.0,
-            },
-            histogram: vec![],
+/// Running totals of a simulation, fed one run at a time in seed order
+#[derive(Default)]
+struct SimulationTally {
+    runs: u32,
+    failed_runs: u32,
+    successes: u32,
+    room_counts: Vec<f64>,
+    path_lengths: Vec<f64>,
+    enemy_counts: Vec<f64>,
+    item_counts: Vec<f64>,
+    transition_matrix: HashMap<String, HashMap<String, u32>>,
+    // Per constraint id: (runs evaluated, runs passed)
+    constraint_tallies: HashMap<String, (u32, u32)>,
+    warnings: Vec<String>,
+}
+
+impl SimulationTally {
+    fn add(&mut self, outcome: Result<RunMetrics, String>) {
+        self.runs += 1;
+        let Ok(metrics) = outcome else {
+            self.failed_runs += 1;
+            return;
         };
+
+        self.room_counts.push(metrics.room_count);
+        self.path_lengths.push(metrics.path_length);
+        self.enemy_counts.push(metrics.enemy_count);
+        self.item_counts.push(metrics.item_count);
+        for (from, row) in metrics.transitions {
+            let target = self.transition_matrix.entry(from).or_default();
+            for (to, count) in row {
+                *target.entry(to).or_default() += count;
+            }
+        }
+        for result in &metrics.constraint_results {
+            let tally = self
+                .constraint_tallies
+                .entry(result.constraint_id.clone())
+                .or_default();
+            tally.0 += 1;
+            if result.passed {
+                tally.1 += 1;
+            }
+        }
+        if metrics.success {
+            self.successes += 1;
+        }
     }
 
-    let mut sorted = data.to_vec();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    fn finish(mut self, config: &SimulationConfig, start: Instant) -> SimulationResults {
+        if self.failed_runs > 0 {
+            self.warnings
+                .push(format!("{} runs failed graph execution", self.failed_runs));
+        }
 
-    let min = sorted[0];
-    let max = sorted[sorted.len() - 1];
-    let mean = data.iter().sum::<f64>() / data.len() as f64;
-    let median = sorted[sorted.len() / 2];
+        SimulationResults {
+            config: config.clone(),
+            runs: self.runs,
+            success_rate: if self.runs == 0 {
+                0.0
+            } else {
+                self.successes as f64 / self.runs as f64
+            },
+            duration_ms: start.elapsed().as_millis() as u64,
+            statistics: SimulationStatistics {
+                room_count: calculate_stats(&self.room_counts),
+                path_length: calculate_stats(&self.path_lengths),
+                enemy_count: calculate_stats(&self.enemy_counts),
+                item_count: calculate_stats(&self.item_counts),
+            },
+            constraint_results: self
+                .constraint_tallies
+                .into_iter()
+                .map(|(id, (evaluated, passed))| {
+                    let stats = ConstraintStats {
+                        pass_rate: passed as f64 / evaluated as f64,
+                        violations: evaluated - passed,
+                    };
+                    (id, stats)
+                })
+                .collect(),
+            warnings: self.warnings,
+            transition_matrix: self.transition_matrix,
+        }
+    }
+}
 
-    let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64;
-    let std_dev = variance.sqrt();
+/// Run a simulation and return the single run that best matches `objective`
+#[command]
+pub fn simulate_and_select(
+    config: SimulationConfig,
+    objective: SelectionObjective,
+) -> Result<GenerationResult, String> {
+    let start = Instant::now();
+    let seed_start = config.seed_start.unwrap_or(0);
 
-    let percentile = |p: f64| -> f64 {
-        let idx = ((p / 100.0) * (sorted.len() - 1) as f64) as usize;
-        sorted[idx.min(sorted.len() - 1)]
-    };
+    let (seed, layout) = (0..config.run_count)
+        .filter_map(|i| {
+            let seed = seed_start + i as u64;
+            simulate_layout(&config, seed)
+                .ok()
+                .map(|layout| (seed, layout))
+        })
+        .max_by(|(_, a), (_, b)| {
+            objective_score(a, &objective).total_cmp(&objective_score(b, &objective))
+        })
+        .ok_or("Simulation run count must be at least 1")?;
 
-    // Generate histogram with 10 buckets
-    let bucket_size = (max - min) / 10.0;
-    let mut histogram = vec![
-        HistogramBucket {
-            bucket: 0.0,
-            count: 0
-        };
-        10
-    ];
-    for (i, h) in histogram.iter_mut().enumerate() {
-        h.bucket = min + (i as f64 * bucket_size);
-    }
-
-    for val in data {
-        let bucket_idx = ((val - min) / bucket_size).floor() as usize;
-        let bucket_idx = bucket_idx.min(9);
-        histogram[bucket_idx].count += 1;
-    }
-
-    DistributionStats {
-        min,
-        max,
-        mean,
-        median,
-        std_dev,
-        percentiles: Percentiles {
-            p5: percentile(5.0),
-            p25: percentile(25.0),
-            p75: percentile(75.0),
-            p95: percentile(95.0),
+    Ok(GenerationResult {
+        seed,
+        timestamp: unix_timestamp(),
+        success: true,
+        data: Some(layout),
+        loot: None,
+        constraint_results: vec![],
+        metadata: GenerationMetadata {
+            node_executions: 0,
+            retry_count: 0,
+            rng_draws: 0,
         },
-        histogram,
+        errors: vec![],
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Shannon entropy (bits) of the room-count distribution over a simulation.
+///
+/// Near zero means the generator repeats itself; higher means more varied output.
+#[command]
+pub fn output_entropy(config: SimulationConfig) -> Result<f64, String> {
+    let seed_start = config.seed_start.unwrap_or(0);
+    let room_counts: Vec<u64> = (0..config.run_count)
+        .filter_map(|i| simulate_layout(&config, seed_start + i as u64).ok())
+        .map(|layout| layout.rooms.len() as u64)
+        .collect();
+
+    Ok(analysis::shannon_entropy(&room_counts))
+}
+
+/// Total node executions per node type over `runs` seeds (0..runs).
+///
+/// Runs that fail still contribute the nodes they executed before failing.
+#[command]
+pub fn node_execution_profile(
+    generator: Generator,
+    runs: u32,
+) -> Result<HashMap<String, u64>, String> {
+    let mut profile = HashMap::new();
+    for seed in 0..runs as u64 {
+        let mut executor = GraphExecutor::new(seed, HashMap::new());
+        let _ = executor.execute(&generator);
+        for (node_type, count) in executor.executions_by_type() {
+            let name = serde_json::to_value(node_type)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .ok_or_else(|| format!("Failed to name node type: {:?}", node_type))?;
+            *profile.entry(name).or_default() += count;
+        }
+    }
+    Ok(profile)
+}
+
+/// Every distinct room type produced over `samples` seeds (0..samples).
+///
+/// Declared types missing from the result can't be reached through the graph
+/// or its weights. Runs that fail contribute nothing.
+#[command]
+pub fn reachable_room_types(generator: Generator, samples: u32) -> Result<HashSet<String>, String> {
+    let mut room_types = HashSet::new();
+    for seed in 0..samples as u64 {
+        if let Ok(layout) = GraphExecutor::new(seed, HashMap::new()).execute(&generator) {
+            room_types.extend(layout.rooms.into_iter().map(|r| r.room_type));
+        }
+    }
+    Ok(room_types)
+}
+
+/// Generate the layout a simulation produces for one seed, through the
+/// config's generator graph when it has one
+fn simulate_layout(config: &SimulationConfig, seed: u64) -> Result<DungeonLayout, String> {
+    match &config.generator {
+        Some(generator) => GraphExecutor::new(seed, config.parameters.clone())
+            .execute(generator)
+            .map_err(|e| format!("Graph execution error: {}", e)),
+        None => {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            Ok(generate_dungeon(&mut rng))
+        }
     }
 }
 
+/// Higher is better: closeness to the target room count plus weighted connectivity
+fn objective_score(layout: &DungeonLayout, objective: &SelectionObjective) -> f64 {
+    let room_penalty = objective
+        .target_room_count
+        .map(|target| (layout.rooms.len() as f64 - target).abs())
+        .unwrap_or(0.0);
+    objective.connectivity_weight * analysis::reachable_area_fraction(layout) - room_penalty
+}
+
 #[command]
-pub fn cancel_simulation() -> Result<(), String> {
-    // In a full implementation, this would signal a running simulation to stop
+pub fn cancel_simulation(state: State<'_, SimulationState>) -> Result<(), String> {
+    state.cancelled.store(true, Ordering::SeqCst);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator_of_type(generator_type: &str) -> Generator {
+        serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Generator",
+            "type": generator_type,
+            "graph": { "nodes": [], "edges": [] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_output_descriptor_for_loot_generator() {
+        let descriptor = output_descriptor(generator_of_type("loot")).unwrap();
+
+        assert_eq!(descriptor.result_type, "lootList");
+        assert!(!descriptor.spatial);
+        assert!(!descriptor.notes.is_empty());
+    }
+
+    #[test]
+    fn test_loot_generator_produces_items_without_rooms() {
+        let generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "loot",
+            "name": "Chest",
+            "type": "loot",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } },
+                    { "id": "room", "type": "room", "position": { "x": 100.0, "y": 0.0 },
+                      "data": { "label": "Room" } },
+                    { "id": "drop", "type": "loot_drop", "position": { "x": 200.0, "y": 0.0 },
+                      "data": { "label": "Drop", "itemCount": 3, "itemType": "gold" } },
+                    { "id": "output", "type": "output", "position": { "x": 300.0, "y": 0.0 },
+                      "data": { "label": "Output" } }
+                ],
+                "edges": [
+                    { "id": "e1", "source": { "nodeId": "start", "portId": "out" },
+                      "target": { "nodeId": "room", "portId": "in" } },
+                    { "id": "e2", "source": { "nodeId": "room", "portId": "out" },
+                      "target": { "nodeId": "drop", "portId": "in" } },
+                    { "id": "e3", "source": { "nodeId": "drop", "portId": "out" },
+                      "target": { "nodeId": "output", "portId": "in" } }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let result = generate_once(GenerationRequest {
+            generator_id: "loot".to_string(),
+            seed: 7,
+            parameters: HashMap::new(),
+            generator: Some(generator),
+        })
+        .unwrap();
+
+        assert!(result.success);
+        assert!(result.data.is_none());
+        let loot = result.loot.unwrap();
+        assert_eq!(loot.items.len(), 3);
+        assert!(loot.items.iter().all(|i| i.item_type == "gold"));
+    }
+
+    fn empty_graph_request(strict: bool) -> GenerationRequest {
+        GenerationRequest {
+            generator_id: "gen".to_string(),
+            seed: 1,
+            parameters: HashMap::from([("strict".to_string(), serde_json::json!(strict))]),
+            generator: Some(generator_of_type("dungeon")),
+        }
+    }
+
+    #[test]
+    fn test_empty_graph_in_strict_mode_does_not_fall_back() {
+        let result = generate_once(empty_graph_request(true)).unwrap();
+
+        assert!(!result.success);
+        assert!(result.data.is_none());
+        assert!(result.errors[0].contains("empty"));
+    }
+
+    #[test]
+    fn test_empty_graph_without_strict_falls_back() {
+        let result = generate_once(empty_graph_request(false)).unwrap();
+
+        assert!(!result.success);
+        assert!(result.data.is_some());
+    }
+
+    #[test]
+    fn test_fallback_keeps_node_execution_count() {
+        let generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Generator",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } }
+                ],
+                "edges": []
+            }
+        }))
+        .unwrap();
+        // A zero timeout fails right after the first node runs
+        let request = GenerationRequest {
+            generator_id: "gen".to_string(),
+            seed: 1,
+            parameters: HashMap::from([("timeoutMs".to_string(), serde_json::json!(0))]),
+            generator: Some(generator),
+        };
+
+        let result = generate_once(request).unwrap();
+
+        assert!(!result.success);
+        assert!(result.data.is_some());
+        assert_eq!(result.metadata.node_executions, 1);
+    }
+
+    #[test]
+    fn test_output_descriptor_for_dungeon_generator() {
+        let descriptor = output_descriptor(generator_of_type("dungeon")).unwrap();
+
+        assert_eq!(descriptor.result_type, "dungeonLayout");
+        assert!(descriptor.spatial);
+    }
+
+    #[test]
+    fn test_simulate_and_select_returns_best_run() {
+        let config = SimulationConfig {
+            generator_id: "gen".to_string(),
+            run_count: 20,
+            seed_start: Some(100),
+            parameters: HashMap::new(),
+            constraints: vec![],
+            short_circuit: false,
+            generator: None,
+        };
+        let objective = SelectionObjective {
+            target_room_count: Some(5.0),
+            connectivity_weight: 1.0,
+        };
+
+        let best = simulate_and_select(config.clone(), objective.clone()).unwrap();
+        let best_score = objective_score(best.data.as_ref().unwrap(), &objective);
+
+        for seed in 100..120 {
+            let layout = simulate_layout(&config, seed).unwrap();
+            assert!(best_score >= objective_score(&layout, &objective));
+        }
+    }
+
+    #[test]
+    fn test_output_entropy_of_varied_generator() {
+        let config = SimulationConfig {
+            generator_id: "gen".to_string(),
+            run_count: 50,
+            seed_start: Some(0),
+            parameters: HashMap::new(),
+            constraints: vec![],
+            short_circuit: false,
+            generator: None,
+        };
+
+        // The legacy generator picks 4..=8 rooms, so counts vary across seeds
+        assert!(output_entropy(config).unwrap() > 1.0);
+    }
+
+    #[test]
+    fn test_seed_gallery_returns_distinct_seeds() {
+        let request = GenerationRequest {
+            generator_id: "gen".to_string(),
+            seed: 40,
+            parameters: HashMap::new(),
+            generator: None,
+        };
+
+        let gallery = seed_gallery(request, 6).unwrap();
+        let seeds: std::collections::HashSet<u64> = gallery.iter().map(|s| s.seed).collect();
+
+        assert_eq!(gallery.len(), 6);
+        assert_eq!(seeds.len(), 6);
+        assert!(gallery
+            .iter()
+            .all(|s| s.room_count > 0 && s.bounds.is_some()));
+        assert!(gallery.iter().all(|s| s.density.len() == GALLERY_GRID_SIZE));
+    }
+
+    #[test]
+    fn test_rng_draws_stable_for_fixed_seed() {
+        let request = GenerationRequest {
+            generator_id: "gen".to_string(),
+            seed: 99,
+            parameters: HashMap::new(),
+            generator: None,
+        };
+
+        let first = generate_once(request.clone()).unwrap();
+        let second = generate_once(request).unwrap();
+
+        assert!(first.metadata.rng_draws > 0);
+        assert_eq!(first.metadata.rng_draws, second.metadata.rng_draws);
+    }
+
+    /// Start picks at random between a one-room and a three-room path and
+    /// requires at least three rooms, so only some seeds satisfy it
+    fn seed_dependent_generator() -> Generator {
+        let node = |id: &str, node_type: &str| {
+            serde_json::json!({ "id": id, "type": node_type, "position": { "x": 0.0, "y": 0.0 },
+                                "data": { "label": id } })
+        };
+        let edge = |id: &str, from: &str, to: &str| {
+            serde_json::json!({ "id": id,
+                                "source": { "nodeId": from, "portId": "out" },
+                                "target": { "nodeId": to, "portId": "in" } })
+        };
+        serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Coin flip",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    node("start", "start"),
+                    node("pick", "random_select"),
+                    node("small", "room"),
+                    node("large", "room_chain"),
+                ],
+                "edges": [
+                    edge("e0", "start", "pick"),
+                    edge("e1", "pick", "small"),
+                    edge("e2", "pick", "large"),
+                ]
+            },
+            "constraints": [{
+                "id": "enough_rooms",
+                "type": "count",
+                "parameters": { "min": 3 },
+                "error_message": "Too few rooms",
+                "severity": "error"
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_failing_constraints_retry_with_derived_seed() {
+        let generator = seed_dependent_generator();
+        let passes = |seed: u64| {
+            GraphExecutor::new(seed, HashMap::new())
+                .execute(&generator)
+                .unwrap()
+                .rooms
+                .len()
+                >= 3
+        };
+        let first_retry = |seed: u64| retry_seeds(seed).nth(1).unwrap();
+        let seed = (0..100)
+            .find(|&s| !passes(s) && passes(first_retry(s)))
+            .expect("some seed fails once and then passes");
+        let request = |max_retries: u64| GenerationRequest {
+            generator_id: "gen".to_string(),
+            seed,
+            parameters: HashMap::from([("maxRetries".to_string(), serde_json::json!(max_retries))]),
+            generator: Some(generator.clone()),
+        };
+
+        let result = generate_once(request(5)).unwrap();
+        assert!(result.success);
+        assert_eq!(result.metadata.retry_count, 1);
+        assert_eq!(result.seed, first_retry(seed));
+        assert!(result.constraint_results[0].passed);
+
+        let result = generate_once(request(0)).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.metadata.retry_count, 0);
+        assert!(result.errors[0].contains("enough_rooms"));
+    }
+
+    #[test]
+    fn test_max_retries_is_clamped() {
+        let params = |value: serde_json::Value| HashMap::from([("maxRetries".to_string(), value)]);
+
+        assert_eq!(max_retries(&HashMap::new()), DEFAULT_MAX_RETRIES);
+        assert_eq!(max_retries(&params(serde_json::json!(3))), 3);
+        assert_eq!(
+            max_retries(&params(serde_json::json!(u64::MAX))),
+            MAX_RETRIES_LIMIT
+        );
+    }
+
+    #[test]
+    fn test_constraint_downgraded_to_warning_does_not_retry() {
+        let generator = seed_dependent_generator();
+        let seed = (0..100)
+            .find(|&s| {
+                GraphExecutor::new(s, HashMap::new())
+                    .execute(&generator)
+                    .unwrap()
+                    .rooms
+                    .len()
+                    < 3
+            })
+            .expect("some seed fails the room count");
+        let request = GenerationRequest {
+            generator_id: "gen".to_string(),
+            seed,
+            parameters: HashMap::from([(
+                "constraintSeverity".to_string(),
+                serde_json::json!({ "enough_rooms": "warning" }),
+            )]),
+            generator: Some(generator),
+        };
+
+        let result = generate_once(request).unwrap();
+        assert!(result.success);
+        assert_eq!(result.seed, seed);
+        assert_eq!(result.metadata.retry_count, 0);
+        assert!(!result.constraint_results[0].passed);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_batch_items_match_generate_once_at_their_seed() {
+        let request = GenerationRequest {
+            generator_id: "gen".to_string(),
+            seed: 40,
+            parameters: HashMap::from([("maxRetries".to_string(), serde_json::json!(5))]),
+            generator: Some(seed_dependent_generator()),
+        };
+
+        let batch = generate_batch(request.clone(), 8).unwrap();
+        assert_eq!(batch.len(), 8);
+        for (i, item) in batch.iter().enumerate() {
+            let single = generate_once(GenerationRequest {
+                seed: 40 + i as u64,
+                ..request.clone()
+            })
+            .unwrap();
+            assert_eq!(item.seed, single.seed);
+            assert_eq!(item.metadata.retry_count, single.metadata.retry_count);
+            assert_eq!(
+                serde_json::to_value(&item.data).unwrap(),
+                serde_json::to_value(&single.data).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_adjacency_report_elevates_engineered_pair() {
+        // combat is always followed by treasure; plain rooms fill the rest
+        let types = [
+            "combat", "treasure", "plain", "plain", "combat", "treasure", "plain", "plain",
+        ];
+        let mut nodes = vec![serde_json::json!({
+            "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+            "data": { "label": "Start" }
+        })];
+        let mut edges = vec![];
+        for (i, room_type) in types.iter().enumerate() {
+            nodes.push(serde_json::json!({
+                "id": format!("r{}", i), "type": "room", "position": { "x": 0.0, "y": 0.0 },
+                "data": { "label": "Room", "roomType": room_type }
+            }));
+            let source = if i == 0 {
+                "start".to_string()
+            } else {
+                format!("r{}", i - 1)
+            };
+            edges.push(serde_json::json!({
+                "id": format!("e{}", i),
+                "source": { "nodeId": source, "portId": "out" },
+                "target": { "nodeId": format!("r{}", i), "portId": "in" }
+            }));
+        }
+        let generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "gen", "name": "Adjacency", "type": "dungeon",
+            "graph": { "nodes": nodes, "edges": edges }
+        }))
+        .unwrap();
+        let config = SimulationConfig {
+            generator_id: "gen".to_string(),
+            run_count: 20,
+            seed_start: None,
+            parameters: HashMap::new(),
+            constraints: vec![],
+            short_circuit: false,
+            generator: Some(generator),
+        };
+
+        let report = adjacency_report(config).unwrap();
+        assert_eq!(report.runs, 20);
+        assert_eq!(report.total_transitions, 20 * 7);
+        let lift = |from: &str, to: &str| {
+            report
+                .pairs
+                .iter()
+                .find(|p| p.from_type == from && p.to_type == to)
+                .map(|p| p.lift)
+        };
+        // 2 of 7 transitions, against base rates of 2/7 out of combat and 2/7 into treasure
+        assert!((lift("combat", "treasure").unwrap() - 3.5).abs() < 1e-9);
+        assert!(lift("plain", "plain").unwrap() < 2.0);
+        assert_eq!(lift("combat", "plain"), None);
+    }
+
+    #[test]
+    fn test_node_execution_profile_of_loop_heavy_graph() {
+        let generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Loop",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } },
+                    { "id": "loop", "type": "loop", "position": { "x": 100.0, "y": 0.0 },
+                      "data": { "label": "Loop", "iterations": 8 } },
+                    { "id": "room", "type": "room", "position": { "x": 200.0, "y": 0.0 },
+                      "data": { "label": "Room" } }
+                ],
+                "edges": [
+                    { "id": "e1", "source": { "nodeId": "start", "portId": "out" },
+                      "target": { "nodeId": "loop", "portId": "in" } },
+                    { "id": "e2", "source": { "nodeId": "loop", "portId": "out" },
+                      "target": { "nodeId": "room", "portId": "in" } }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let profile = node_execution_profile(generator, 4).unwrap();
+
+        assert_eq!(profile["start"], 4);
+        assert_eq!(profile["loop"], 4);
+        assert_eq!(profile["room"], 32);
+    }
+
+    #[test]
+    fn test_cancelled_simulation_stops_early() {
+        let config: SimulationConfig = serde_json::from_value(serde_json::json!({
+            "generatorId": "gen",
+            "runCount": 10_000_000,
+            "constraints": [],
+        }))
+        .unwrap();
+        let state = std::sync::Arc::new(SimulationState::default());
+
+        let running = {
+            let state = state.clone();
+            let config = config.clone();
+            std::thread::spawn(move || simulate(&config, &state.cancelled, |_| {}))
+        };
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        state.cancelled.store(true, Ordering::SeqCst);
+        let results = running.join().unwrap().unwrap();
+
+        assert!(results.runs < config.run_count);
+        assert!(results.warnings[0].contains("cancelled"));
+    }
+
+    #[test]
+    fn test_simulation_runs_the_generator_graph() {
+        let generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Four rooms",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } },
+                    { "id": "loop", "type": "loop", "position": { "x": 100.0, "y": 0.0 },
+                      "data": { "label": "Loop", "iterations": 4 } },
+                    { "id": "room", "type": "room", "position": { "x": 200.0, "y": 0.0 },
+                      "data": { "label": "Room" } }
+                ],
+                "edges": [
+                    { "id": "e1", "source": { "nodeId": "start", "portId": "out" },
+                      "target": { "nodeId": "loop", "portId": "in" } },
+                    { "id": "e2", "source": { "nodeId": "loop", "portId": "out" },
+                      "target": { "nodeId": "room", "portId": "in" } }
+                ]
+            }
+        }))
+        .unwrap();
+        let config = SimulationConfig {
+            generator_id: "gen".to_string(),
+            run_count: 50,
+            seed_start: Some(0),
+            parameters: HashMap::new(),
+            constraints: vec![],
+            short_circuit: false,
+            generator: Some(generator),
+        };
+
+        let results = simulate(&config, &AtomicBool::new(false), |_| {}).unwrap();
+
+        assert_eq!(results.runs, 50);
+        assert_eq!(results.statistics.room_count.mean, 4.0);
+        assert_eq!(results.statistics.room_count.std_dev, 0.0);
+        assert!(results.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_simulation_progress_climbs_to_total() {
+        let config: SimulationConfig = serde_json::from_value(serde_json::json!({
+            "generatorId": "gen",
+            "runCount": 600,
+            "constraints": [],
+        }))
+        .unwrap();
+
+        let mut events = vec![];
+        simulate(&config, &AtomicBool::new(false), |progress| {
+            events.push(progress)
+        })
+        .unwrap();
+
+        let completed: Vec<u32> = events.iter().map(|e| e.completed).collect();
+        assert_eq!(completed, vec![256, 512, 600]);
+        assert!(events.iter().all(|e| e.total == 600));
+        assert!(events
+            .windows(2)
+            .all(|w| w[0].elapsed_ms <= w[1].elapsed_ms));
+    }
+
+    #[test]
+    fn test_parallel_simulation_matches_serial_fold() {
+        let config: SimulationConfig = serde_json::from_value(serde_json::json!({
+            "generatorId": "gen",
+            "runCount": 700,
+            "seedStart": 42,
+            "constraints": [{
+                "id": "enough_rooms",
+                "type": "count",
+                "parameters": { "min": 6 },
+                "error_message": "Too few rooms",
+                "severity": "error"
+            }],
+        }))
+        .unwrap();
+
+        let parallel = simulate(&config, &AtomicBool::new(false), |_| {}).unwrap();
+        let mut tally = SimulationTally::default();
+        for i in 0..config.run_count {
+            tally.add(simulate_run(&config, 42 + i as u64));
+        }
+        let serial = tally.finish(&config, Instant::now());
+
+        assert_eq!(parallel.runs, serial.runs);
+        assert_eq!(
+            parallel.success_rate.to_bits(),
+            serial.success_rate.to_bits()
+        );
+        assert_eq!(
+            serde_json::to_value(&parallel.statistics).unwrap(),
+            serde_json::to_value(&serial.statistics).unwrap()
+        );
+        assert_eq!(parallel.transition_matrix, serial.transition_matrix);
+        assert_eq!(
+            parallel.constraint_results["enough_rooms"].violations,
+            serial.constraint_results["enough_rooms"].violations
+        );
+    }
+
+    #[test]
+    fn test_unreachable_room_type_is_never_produced() {
+        // The boss room is declared but no edge leads to it
+        let generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "No boss",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } },
+                    { "id": "pick", "type": "random_select", "position": { "x": 100.0, "y": 0.0 },
+                      "data": { "label": "Pick" } },
+                    { "id": "normal", "type": "room", "position": { "x": 200.0, "y": 0.0 },
+                      "data": { "label": "Normal", "roomType": "normal" } },
+                    { "id": "treasure", "type": "room", "position": { "x": 200.0, "y": 100.0 },
+                      "data": { "label": "Treasure", "roomType": "treasure" } },
+                    { "id": "boss", "type": "room", "position": { "x": 200.0, "y": 200.0 },
+                      "data": { "label": "Boss", "roomType": "boss" } }
+                ],
+                "edges": [
+                    { "id": "e1", "source": { "nodeId": "start", "portId": "out" },
+                      "target": { "nodeId": "pick", "portId": "in" } },
+                    { "id": "e2", "source": { "nodeId": "pick", "portId": "out" },
+                      "target": { "nodeId": "normal", "portId": "in" } },
+                    { "id": "e3", "source": { "nodeId": "pick", "portId": "out" },
+                      "target": { "nodeId": "treasure", "portId": "in" } }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let room_types = reachable_room_types(generator, 100).unwrap();
+
+        assert_eq!(
+            room_types,
+            HashSet::from(["normal".to_string(), "treasure".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_zero_weight_room_type_is_never_reached() {
+        let generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "No boss",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } },
+                    { "id": "pick", "type": "random_select", "position": { "x": 100.0, "y": 0.0 },
+                      "data": { "label": "Pick" } },
+                    { "id": "normal", "type": "room", "position": { "x": 200.0, "y": 0.0 },
+                      "data": { "label": "Normal", "roomType": "normal" } },
+                    { "id": "boss", "type": "room", "position": { "x": 200.0, "y": 200.0 },
+                      "data": { "label": "Boss", "roomType": "boss" } }
+                ],
+                "edges": [
+                    { "id": "e1", "source": { "nodeId": "start", "portId": "out" },
+                      "target": { "nodeId": "pick", "portId": "in" } },
+                    { "id": "e2", "source": { "nodeId": "pick", "portId": "out" },
+                      "target": { "nodeId": "normal", "portId": "in" } },
+                    { "id": "e3", "source": { "nodeId": "pick", "portId": "out" },
+                      "target": { "nodeId": "boss", "portId": "in" },
+                      "metadata": { "weight": 0.0 } }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let room_types = reachable_room_types(generator, 100).unwrap();
+
+        assert_eq!(room_types, HashSet::from(["normal".to_string()]));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::engine::{editing, export, integrity, Direction, RoomConfigJson};
+use crate::models::{DungeonLayout, ExportTarget, GenerationResult, MinimapData};
+use std::fs;
+use tauri::command;
+
+#[command]
+pub fn layout_checksum(layout: DungeonLayout) -> Result<String, String> {
+    integrity::layout_checksum(&layout)
+}
+
+#[command]
+pub fn verify_layout(layout: DungeonLayout, checksum: String) -> Result<bool, String> {
+    Ok(integrity::layout_checksum(&layout)?.eq_ignore_ascii_case(checksum.trim()))
+}
+
+/// Write a generated layout to `path` as a Tiled `.tmx` map
+#[command]
+pub fn export_tiled(result: GenerationResult, path: String) -> Result<(), String> {
+    let layout = result
+        .data
+        .as_ref()
+        .ok_or("Generation result has no layout to export")?;
+
+    fs::write(&path, export::to_tiled_tmx(layout)?)
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Collapse spawn points within `min_distance` of each other in the same room
+#[command]
+pub fn dedupe_spawns(layout: DungeonLayout, min_distance: f64) -> Result<DungeonLayout, String> {
+    let mut layout = layout;
+    editing::dedupe_spawns(&mut layout, min_distance);
+    Ok(layout)
+}
+
+/// Layout scaled to fit a `target_size` square, for the editor minimap
+#[command]
+pub fn minimap(layout: DungeonLayout, target_size: f64) -> Result<MinimapData, String> {
+    Ok(export::to_minimap(&layout, target_size))
+}
+
+/// Room graph as `{ nodes: [{id, type, x, y}], links: [{source, target}] }` for graph-viz libraries
+#[command]
+pub fn layout_graph_json(layout: DungeonLayout) -> Result<serde_json::Value, String> {
+    Ok(export::to_node_link(&layout))
+}
+
+/// Serialize a layout as JSON or as a typed TypeScript/Rust/C#/GDScript literal
+#[command]
+pub fn export_layout(layout: DungeonLayout, target: ExportTarget) -> Result<String, String> {
+    export::export_layout(&layout, &target)
+}
+
+/// Add a room beside `near_room_id` in `direction` ("right", "left", "up", "down")
+#[command]
+pub fn append_room(
+    layout: DungeonLayout,
+    config: RoomConfigJson,
+    near_room_id: String,
+    direction: String,
+) -> Result<DungeonLayout, String> {
+    let direction = Direction::from_name(&direction)
+        .ok_or_else(|| format!("Unknown direction: {}", direction))?;
+    let mut layout = layout;
+    editing::append_room(&mut layout, &config.into(), &near_room_id, direction)
+        .map_err(|e| format!("Failed to append room: {}", e))?;
+    Ok(layout)
+}
+
+/// Delete a room, reconnecting its former neighbors if that would split the dungeon
+#[command]
+pub fn remove_room(layout: DungeonLayout, room_id: String) -> Result<DungeonLayout, String> {
+    let mut layout = layout;
+    editing::remove_room(&mut layout, &room_id)
+        .map_err(|e| format!("Failed to remove room: {}", e))?;
+    Ok(layout)
+}
+
+/// Discard a layout's connections and derive new ones with the "nearest", "mst" or "delaunay" strategy
+#[command]
+pub fn rebuild_connections(
+    layout: DungeonLayout,
+    strategy: String,
+) -> Result<DungeonLayout, String> {
+    let mut layout = layout;
+    editing::rebuild_connections(&mut layout, &strategy)
+        .map_err(|e| format!("Failed to rebuild connections: {}", e))?;
+    Ok(layout)
+}
+
+/// Re-roll colors, entity variants and rotations from `seed`, keeping geometry fixed
+#[command]
+pub fn reskin_layout(layout: DungeonLayout, seed: u64) -> Result<DungeonLayout, String> {
+    let mut layout = layout;
+    editing::reskin_layout(&mut layout, seed);
+    Ok(layout)
+}
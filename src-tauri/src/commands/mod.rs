@@ -1,5 +1,11 @@
+pub mod analysis;
 pub mod generation;
+pub mod layout;
 pub mod project;
+pub mod validation;
 
+pub use analysis::*;
 pub use generation::*;
+pub use layout::*;
 pub use project::*;
+pub use validation::*;
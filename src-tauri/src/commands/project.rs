@@ -1,7 +1,11 @@
-use crate::models::{Project, RecentProject};
+use crate::models::{Project, RecentProject, SaveProgress};
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 
-use tauri::command;
+use tauri::{command, AppHandle, Emitter, Manager};
 
 #[command]
 pub fn create_project(name: String) -> Result<Project, String> {
@@ -20,27 +24,270 @@ pub fn create_project(name: String) -> Result<Project, String> {
 }
 
 #[command]
-pub fn open_project(path: String) -> Result<Project, String> {
+pub fn open_project(app: AppHandle, path: String) -> Result<Project, String> {
     let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
 
     let project: Project =
         serde_json::from_str(&content).map_err(|e| format!("Failed to parse project: {}", e))?;
 
+    // The recent list is a convenience; failing to update it shouldn't fail the open
+    let _ = recent_projects_file(&app)
+        .and_then(|file| record_recent_project(&file, &project.id, &project.name, &path));
+
     Ok(project)
 }
 
 #[command]
-pub fn save_project(project: Project, path: String) -> Result<(), String> {
+pub fn save_project(app: AppHandle, project: Project, path: String) -> Result<(), String> {
     let content = serde_json::to_string_pretty(&project)
         .map_err(|e| format!("Failed to serialize project: {}", e))?;
 
     fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))?;
 
+    let _ = recent_projects_file(&app)
+        .and_then(|file| record_recent_project(&file, &project.id, &project.name, &path));
+
+    Ok(())
+}
+
+/// Save a project one generator at a time, emitting `save-progress` after each.
+///
+/// Writes to `<path>.tmp` and renames it over `path` once complete, so a failed
+/// save never leaves a truncated project behind.
+#[command(async)]
+pub fn save_project_streaming(
+    app: AppHandle,
+    project: Project,
+    path: String,
+) -> Result<(), String> {
+    let (id, name) = (project.id.clone(), project.name.clone());
+    write_project_streaming(project, &path, |progress| {
+        let _ = app.emit("save-progress", progress);
+    })?;
+
+    let _ =
+        recent_projects_file(&app).and_then(|file| record_recent_project(&file, &id, &name, &path));
+
     Ok(())
 }
 
+fn write_project_streaming(
+    project: Project,
+    path: &str,
+    mut on_progress: impl FnMut(SaveProgress),
+) -> Result<(), String> {
+    let mut header = project;
+    let generators = std::mem::take(&mut header.generators);
+    let serde_json::Value::Object(mut fields) =
+        serde_json::to_value(&header).map_err(|e| format!("Failed to serialize project: {}", e))?
+    else {
+        return Err("Failed to serialize project: not an object".to_string());
+    };
+    fields.remove("generators");
+
+    let temp_path = format!("{}.tmp", path);
+    let file = fs::File::create(&temp_path).map_err(|e| format!("Failed to write file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let write_error = |e: std::io::Error| format!("Failed to write file: {}", e);
+    let serialize_error = |e: serde_json::Error| format!("Failed to serialize project: {}", e);
+
+    writer.write_all(b"{\n").map_err(write_error)?;
+    for (key, value) in &fields {
+        write!(writer, "  {}: ", serde_json::Value::from(key.as_str())).map_err(write_error)?;
+        serde_json::to_writer(&mut writer, value).map_err(serialize_error)?;
+        writer.write_all(b",\n").map_err(write_error)?;
+    }
+    writer
+        .write_all(b"  \"generators\": [")
+        .map_err(write_error)?;
+    for (i, generator) in generators.iter().enumerate() {
+        writer
+            .write_all(if i == 0 { b"\n    " } else { b",\n    " })
+            .map_err(write_error)?;
+        serde_json::to_writer(&mut writer, generator).map_err(serialize_error)?;
+        on_progress(SaveProgress {
+            generator_id: generator.id.clone(),
+            saved: i + 1,
+            total: generators.len(),
+        });
+    }
+    writer.write_all(b"\n  ]\n}\n").map_err(write_error)?;
+    writer.flush().map_err(write_error)?;
+    drop(writer);
+
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Recently opened or saved projects, most recent first
+#[command]
+pub fn get_recent_projects(app: AppHandle) -> Result<Vec<RecentProject>, String> {
+    load_recent_projects(&recent_projects_file(&app)?)
+}
+
+const RECENT_PROJECTS_FILE: &str = "recent_projects.json";
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// Location of the recent projects list, in the app config directory
+fn recent_projects_file(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(RECENT_PROJECTS_FILE))
+        .map_err(|e| format!("Failed to locate config directory: {}", e))
+}
+
+/// Read the recent list, sorted by `last_opened` descending; a missing file is an empty list
+fn load_recent_projects(file: &Path) -> Result<Vec<RecentProject>, String> {
+    if !file.exists() {
+        return Ok(vec![]);
+    }
+
+    let content =
+        fs::read_to_string(file).map_err(|e| format!("Failed to read recent projects: {}", e))?;
+    let mut recent: Vec<RecentProject> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse recent projects: {}", e))?;
+
+    recent.sort_by_key(|r| Reverse(chrono::DateTime::parse_from_rfc3339(&r.last_opened).ok()));
+    Ok(recent)
+}
+
+/// Move `path` to the top of the recent list, dropping any older entry for the same path
+fn record_recent_project(file: &Path, id: &str, name: &str, path: &str) -> Result<(), String> {
+    let mut recent = load_recent_projects(file)?;
+    recent.retain(|r| r.path != path);
+    recent.insert(
+        0,
+        RecentProject {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            last_opened: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+    recent.truncate(MAX_RECENT_PROJECTS);
+
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&recent)
+        .map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+    fs::write(file, content).map_err(|e| format!("Failed to write recent projects: {}", e))
+}
+
+/// Count nodes of each type (by its serialized name, e.g. "room_chain") across every generator
 #[command]
-pub fn get_recent_projects() -> Result<Vec<RecentProject>, String> {
-    // For now, return empty. In a full implementation, this would read from a config file
-    Ok(vec![])
+pub fn project_node_usage(project: Project) -> Result<HashMap<String, u32>, String> {
+    let mut usage: HashMap<String, u32> = HashMap::new();
+    for node in project.generators.iter().flat_map(|g| &g.graph.nodes) {
+        let node_type = serde_json::to_value(&node.node_type)
+            .map_err(|e| format!("Failed to serialize node type: {}", e))?;
+        if let Some(name) = node_type.as_str() {
+            *usage.entry(name.to_string()).or_default() += 1;
+        }
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn generator_with_nodes(id: &str, node_types: &[&str]) -> serde_json::Value {
+        let nodes: Vec<serde_json::Value> = node_types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                serde_json::json!({
+                    "id": format!("{}_{}", id, i),
+                    "type": t,
+                    "position": { "x": 0.0, "y": 0.0 },
+                    "data": { "label": t },
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "id": id,
+            "name": id,
+            "type": "dungeon",
+            "graph": { "nodes": nodes, "edges": [] },
+        })
+    }
+
+    #[test]
+    fn test_project_node_usage_aggregates_generators() {
+        let mut project = create_project("Usage".to_string()).unwrap();
+        project.generators = vec![
+            serde_json::from_value(generator_with_nodes(
+                "a",
+                &["start", "room", "room", "output"],
+            ))
+            .unwrap(),
+            serde_json::from_value(generator_with_nodes(
+                "b",
+                &["start", "room_chain", "room", "loot_drop"],
+            ))
+            .unwrap(),
+        ];
+
+        let usage = project_node_usage(project).unwrap();
+
+        assert_eq!(usage["start"], 2);
+        assert_eq!(usage["room"], 3);
+        assert_eq!(usage["room_chain"], 1);
+        assert_eq!(usage["loot_drop"], 1);
+        assert_eq!(usage["output"], 1);
+        assert_eq!(usage.len(), 5);
+    }
+
+    #[test]
+    fn test_recorded_projects_appear_in_recent_order() {
+        let dir = env::temp_dir().join(format!("dungeon-forge-{}", uuid::Uuid::new_v4()));
+        let file = dir.join(RECENT_PROJECTS_FILE);
+        assert!(load_recent_projects(&file).unwrap().is_empty());
+
+        let first = create_project("First".to_string()).unwrap();
+        let second = create_project("Second".to_string()).unwrap();
+        record_recent_project(&file, &first.id, &first.name, "first.json").unwrap();
+        record_recent_project(&file, &second.id, &second.name, "second.json").unwrap();
+
+        let recent = load_recent_projects(&file).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].path, "second.json");
+        assert_eq!(recent[1].path, "first.json");
+
+        // Reopening moves the project back to the top without duplicating it
+        record_recent_project(&file, &first.id, &first.name, "first.json").unwrap();
+        let recent = load_recent_projects(&file).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].id, first.id);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_streaming_save_reports_each_generator() {
+        let mut project = create_project("Streaming".to_string()).unwrap();
+        project.generators = ["a", "b", "c"]
+            .iter()
+            .map(|id| serde_json::from_value(generator_with_nodes(id, &["start", "room"])).unwrap())
+            .collect();
+        let path = env::temp_dir()
+            .join(format!("dungeon-forge-{}.json", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string();
+
+        let mut events = vec![];
+        write_project_streaming(project.clone(), &path, |progress| events.push(progress)).unwrap();
+
+        let ids: Vec<&str> = events.iter().map(|e| e.generator_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert!(events.iter().all(|e| e.total == 3));
+        assert_eq!(events[2].saved, 3);
+
+        let saved: Project = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.id, project.id);
+        assert_eq!(saved.generators.len(), 3);
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+        fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,41 @@
+use crate::engine::{constraints, validation};
+use crate::models::generator::{Constraint, Generator};
+use crate::models::{Asset, ConstraintResult, DungeonLayout};
+use std::collections::HashMap;
+use tauri::command;
+
+/// Check a generator for problems before running it.
+///
+/// Pass the project's `shared_assets` to also verify asset references.
+#[command]
+pub fn validate_generator(
+    generator: Generator,
+    assets: Option<Vec<Asset>>,
+) -> Result<Vec<String>, String> {
+    Ok(validation::validate_generator(
+        &generator,
+        assets.as_deref(),
+    ))
+}
+
+/// Check parameter values entered in the editor against the generator's declared types
+#[command]
+pub fn validate_parameters(
+    generator: Generator,
+    values: HashMap<String, serde_json::Value>,
+) -> Result<Vec<String>, String> {
+    Ok(validation::validate_parameters(&generator, &values))
+}
+
+/// Run constraint evaluators against any layout, e.g. one edited by hand after export
+#[command]
+pub fn check_layout_constraints(
+    layout: DungeonLayout,
+    constraints: Vec<Constraint>,
+) -> Result<Vec<ConstraintResult>, String> {
+    Ok(constraints::evaluate_constraints(
+        &constraints,
+        &layout,
+        false,
+    ))
+}
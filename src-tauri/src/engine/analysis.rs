@@ -0,0 +1,1397 @@
+//! Layout analysis metrics
+//!
+//! Pure functions over a generated `DungeonLayout` used by the analysis
+//! commands to report on connectivity, pacing and population.
+
+use super::layout_graph::{self, LayoutGraph};
+use super::rectangles_overlap;
+use super::stats::calculate_stats;
+use super::RoomGenerator;
+use crate::models::{
+    AdjacencyPair, DifficultyWeights, DistributionStats, DungeonLayout, LayoutPosition, Rectangle,
+    RoomConnection, SpawnPoint,
+};
+use std::collections::HashMap;
+
+/// Fraction of total room area reachable from the start room via connections
+pub fn reachable_area_fraction(layout: &DungeonLayout) -> f64 {
+    let total_area: f64 = layout
+        .rooms
+        .iter()
+        .map(|r| r.bounds.width * r.bounds.height)
+        .sum();
+    if total_area <= 0.0 {
+        return 0.0;
+    }
+
+    let graph = LayoutGraph::new(layout);
+    let reachable_area: f64 = graph
+        .reachable_from_start()
+        .iter()
+        .zip(&layout.rooms)
+        .filter(|(reachable, _)| **reachable)
+        .map(|(_, r)| r.bounds.width * r.bounds.height)
+        .sum();
+
+    reachable_area / total_area
+}
+
+/// Length in rooms of the longest side branch off the critical path that ends in a dead end
+pub fn longest_dead_end(layout: &DungeonLayout) -> usize {
+    let graph = LayoutGraph::new(layout);
+    let critical_path = graph.critical_path();
+    let mut on_path = vec![false; layout.rooms.len()];
+    for &room in &critical_path {
+        on_path[room] = true;
+    }
+
+    let mut longest = 0;
+    for &path_room in &critical_path {
+        for &entry in graph.neighbors(path_room) {
+            if on_path[entry] {
+                continue;
+            }
+
+            // Walk the branch without stepping back onto the critical path
+            let mut depth = vec![None; layout.rooms.len()];
+            let mut queue = std::collections::VecDeque::from([entry]);
+            depth[entry] = Some(1);
+            while let Some(current) = queue.pop_front() {
+                let current_depth = depth[current].unwrap_or(1);
+                let mut is_dead_end = true;
+                for &next in graph.neighbors(current) {
+                    if on_path[next] {
+                        continue;
+                    }
+                    if depth[next].is_none() {
+                        depth[next] = Some(current_depth + 1);
+                        queue.push_back(next);
+                    }
+                    if depth[next] > Some(current_depth) {
+                        is_dead_end = false;
+                    }
+                }
+                if is_dead_end {
+                    longest = longest.max(current_depth);
+                }
+            }
+        }
+    }
+
+    longest
+}
+
+/// Average room distance from the start room over the rooms it can reach (the start counts as 0).
+///
+/// 0 for empty layouts.
+pub fn mean_depth(layout: &DungeonLayout) -> f64 {
+    let graph = LayoutGraph::new(layout);
+    let Some(start) = graph.start_room() else {
+        return 0.0;
+    };
+    let depths: Vec<usize> = graph.distances_from(start).into_iter().flatten().collect();
+    depths.iter().sum::<usize>() as f64 / depths.len() as f64
+}
+
+/// Rooms on the longest shortest path (the room graph's diameter) over all rooms.
+///
+/// 1 for a straight chain, near 0 for a hub with many spokes. Empty layouts score 0.
+pub fn linearity(layout: &DungeonLayout) -> f64 {
+    if layout.rooms.is_empty() {
+        return 0.0;
+    }
+    let graph = LayoutGraph::new(layout);
+    let diameter = (0..layout.rooms.len())
+        .flat_map(|room| graph.distances_from(room))
+        .flatten()
+        .max()
+        .unwrap_or(0);
+    (diameter + 1) as f64 / layout.rooms.len() as f64
+}
+
+/// Ids of the articulation rooms: removing any one of them disconnects rooms that were connected.
+///
+/// Reported in layout order.
+pub fn chokepoints(layout: &DungeonLayout) -> Vec<String> {
+    LayoutGraph::new(layout)
+        .articulation_points()
+        .into_iter()
+        .map(|i| layout.rooms[i].id.clone())
+        .collect()
+}
+
+/// Share of steps that re-enter an already visited room on a full-coverage playthrough.
+///
+/// The walk is a depth-first tour from the start room that saves the branch
+/// leading to the exit for last, stops once every reachable room has been seen,
+/// then takes the shortest path to the exit. Linear layouts score 0.
+pub fn backtrack_ratio(layout: &DungeonLayout) -> f64 {
+    let graph = LayoutGraph::new(layout);
+    let (Some(start), Some(exit)) = (graph.start_room(), graph.exit_room()) else {
+        return 0.0;
+    };
+    let to_exit = graph.distances_from(exit);
+
+    let mut walk = Vec::new();
+    let mut visited = vec![false; layout.rooms.len()];
+    tour(&graph, start, &to_exit, &mut visited, &mut walk);
+
+    // Cut the return trip after the last newly discovered room
+    let mut seen = vec![false; layout.rooms.len()];
+    let last_new = walk
+        .iter()
+        .enumerate()
+        .filter(|&(_, &room)| !std::mem::replace(&mut seen[room], true))
+        .map(|(i, _)| i)
+        .last()
+        .unwrap_or(0);
+    walk.truncate(last_new + 1);
+    if let Some(path) = graph.shortest_path(walk[last_new], exit) {
+        walk.extend(path.into_iter().skip(1));
+    }
+
+    let steps = walk.len() - 1;
+    if steps == 0 {
+        return 0.0;
+    }
+    let distinct = visited.iter().filter(|v| **v).count();
+    (steps + 1 - distinct) as f64 / steps as f64
+}
+
+/// Room ids in depth-first pre-order from the start room, for fly-through previews.
+///
+/// Uses the same traversal as `backtrack_ratio`, so the branch toward the exit
+/// comes last. Rooms unreachable from the start follow, one component at a time.
+pub fn tour_order(layout: &DungeonLayout) -> Vec<String> {
+    let graph = LayoutGraph::new(layout);
+    let Some(start) = graph.start_room() else {
+        return vec![];
+    };
+    let to_exit = graph
+        .exit_room()
+        .map(|exit| graph.distances_from(exit))
+        .unwrap_or_else(|| vec![None; layout.rooms.len()]);
+
+    let mut walk = Vec::new();
+    let mut visited = vec![false; layout.rooms.len()];
+    tour(&graph, start, &to_exit, &mut visited, &mut walk);
+    for room in 0..layout.rooms.len() {
+        if !visited[room] {
+            tour(&graph, room, &to_exit, &mut visited, &mut walk);
+        }
+    }
+
+    let mut seen = vec![false; layout.rooms.len()];
+    walk.into_iter()
+        .filter(|&room| !std::mem::replace(&mut seen[room], true))
+        .map(|room| layout.rooms[room].id.clone())
+        .collect()
+}
+
+/// Depth-first tour recording every room entered, including returns to a parent
+fn tour(
+    graph: &LayoutGraph,
+    room: usize,
+    to_exit: &[Option<usize>],
+    visited: &mut [bool],
+    walk: &mut Vec<usize>,
+) {
+    visited[room] = true;
+    walk.push(room);
+
+    // Branches far from the exit first, so the tour ends heading toward it
+    let mut next: Vec<usize> = graph.neighbors(room).to_vec();
+    next.sort_by_key(|&n| std::cmp::Reverse(to_exit[n].unwrap_or(usize::MAX)));
+    for n in next {
+        if !visited[n] {
+            tour(graph, n, to_exit, visited, walk);
+            walk.push(room);
+        }
+    }
+}
+
+/// Enemy count at which the enemy factor reaches one half
+const DIFFICULTY_ENEMY_SCALE: f64 = 10.0;
+/// Enemies per room at which the density factor reaches one half
+const DIFFICULTY_DENSITY_SCALE: f64 = 1.0;
+/// Critical path length (rooms) at which the path factor reaches one half
+const DIFFICULTY_PATH_SCALE: f64 = 5.0;
+
+/// Overall difficulty from 1 (trivial) to 10 (brutal).
+///
+/// Enemy count, enemies per room and critical path length each saturate
+/// smoothly toward 1 (`x / (x + scale)`); branching is the share of rooms with
+/// three or more connections. The weighted mean maps linearly onto 1..=10.
+pub fn difficulty_rating(layout: &DungeonLayout, weights: &DifficultyWeights) -> u8 {
+    let saturate = |value: f64, scale: f64| value / (value + scale);
+    let enemies = (layout
+        .spawn_points
+        .iter()
+        .filter(|s| s.spawn_type == "enemy")
+        .count()
+        + layout
+            .rooms
+            .iter()
+            .flat_map(|r| &r.entities)
+            .filter(|e| e.entity_type == "enemy")
+            .count()) as f64;
+
+    let graph = LayoutGraph::new(layout);
+    let room_count = layout.rooms.len();
+    let (density, branching) = if room_count == 0 {
+        (0.0, 0.0)
+    } else {
+        let branch_rooms = (0..room_count)
+            .filter(|&r| graph.neighbors(r).len() >= 3)
+            .count();
+        (
+            enemies / room_count as f64,
+            branch_rooms as f64 / room_count as f64,
+        )
+    };
+    let path_length = graph.critical_path().len() as f64;
+
+    let factors = [
+        (
+            weights.enemy_count,
+            saturate(enemies, DIFFICULTY_ENEMY_SCALE),
+        ),
+        (
+            weights.encounter_density,
+            saturate(density, DIFFICULTY_DENSITY_SCALE),
+        ),
+        (
+            weights.path_length,
+            saturate(path_length, DIFFICULTY_PATH_SCALE),
+        ),
+        (weights.branching, branching),
+    ];
+    let total_weight: f64 = factors.iter().map(|(w, _)| w.max(0.0)).sum();
+    if total_weight <= 0.0 {
+        return 1;
+    }
+    let score = factors.iter().map(|(w, f)| w.max(0.0) * f).sum::<f64>() / total_weight;
+
+    (1.0 + score * 9.0).round().clamp(1.0, 10.0) as u8
+}
+
+/// Accumulate `from_type -> to_type` room transitions along a layout's connections
+pub fn count_transitions(
+    layout: &DungeonLayout,
+    matrix: &mut HashMap<String, HashMap<String, u32>>,
+) {
+    let room_types: HashMap<&str, &str> = layout
+        .rooms
+        .iter()
+        .map(|r| (r.id.as_str(), r.room_type.as_str()))
+        .collect();
+
+    for conn in &layout.connections {
+        if let (Some(from), Some(to)) = (
+            room_types.get(conn.from_room_id.as_str()),
+            room_types.get(conn.to_room_id.as_str()),
+        ) {
+            *matrix
+                .entry(from.to_string())
+                .or_default()
+                .entry(to.to_string())
+                .or_default() += 1;
+        }
+    }
+}
+
+/// Frequency and lift of every `from_type -> to_type` pair in a transition matrix.
+///
+/// Lift divides a pair's share of all transitions by the product of its base
+/// rates: how often `from_type` starts a transition and how often `to_type`
+/// ends one. Independent types score 1. Pairs are sorted by type names.
+pub fn adjacency_lift(matrix: &HashMap<String, HashMap<String, u32>>) -> Vec<AdjacencyPair> {
+    let total: u32 = matrix.values().flat_map(|row| row.values()).sum();
+    if total == 0 {
+        return vec![];
+    }
+    let mut incoming: HashMap<&str, u32> = HashMap::new();
+    for row in matrix.values() {
+        for (to, count) in row {
+            *incoming.entry(to.as_str()).or_default() += count;
+        }
+    }
+
+    let total = total as f64;
+    let mut pairs: Vec<AdjacencyPair> = matrix
+        .iter()
+        .flat_map(|(from, row)| {
+            let outgoing = row.values().sum::<u32>() as f64 / total;
+            let incoming = &incoming;
+            row.iter().map(move |(to, &count)| {
+                let frequency = count as f64 / total;
+                let expected = outgoing * incoming[to.as_str()] as f64 / total;
+                AdjacencyPair {
+                    from_type: from.clone(),
+                    to_type: to.clone(),
+                    count,
+                    frequency,
+                    lift: frequency / expected,
+                }
+            })
+        })
+        .collect();
+    pairs.sort_by(|a, b| (&a.from_type, &a.to_type).cmp(&(&b.from_type, &b.to_type)));
+    pairs
+}
+
+/// Shannon entropy (bits) of the empirical distribution of discrete values
+pub fn shannon_entropy(values: &[u64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts: HashMap<u64, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(*value).or_default() += 1;
+    }
+
+    let total = values.len() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Minimum spanning tree over room centers as `(from, to, distance)` edges
+pub fn layout_mst(layout: &DungeonLayout) -> Vec<(String, String, f64)> {
+    layout_graph::room_mst(layout)
+}
+
+/// Pairs of room ids whose bounds overlap by more than `tolerance`
+pub fn overlapping_rooms(layout: &DungeonLayout, tolerance: f64) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    for (i, a) in layout.rooms.iter().enumerate() {
+        for b in &layout.rooms[i + 1..] {
+            if rectangles_overlap(&a.bounds, &b.bounds, tolerance) {
+                pairs.push((a.id.clone(), b.id.clone()));
+            }
+        }
+    }
+    pairs
+}
+
+/// Pairs of corridors whose straight door-to-door segments cross.
+///
+/// Each corridor is labelled `"from->to"` by its room ids. Corridors sharing a
+/// room are skipped, since they legitimately meet at that room's doors.
+pub fn crossing_connections(layout: &DungeonLayout) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    for (i, a) in layout.connections.iter().enumerate() {
+        for b in &layout.connections[i + 1..] {
+            let shares_room = [&b.from_room_id, &b.to_room_id]
+                .iter()
+                .any(|id| **id == a.from_room_id || **id == a.to_room_id);
+            if !shares_room
+                && segments_intersect(&a.from_door, &a.to_door, &b.from_door, &b.to_door)
+            {
+                pairs.push((connection_label(a), connection_label(b)));
+            }
+        }
+    }
+    pairs
+}
+
+fn connection_label(conn: &RoomConnection) -> String {
+    format!("{}->{}", conn.from_room_id, conn.to_room_id)
+}
+
+/// Segment intersection via orientation tests, counting touching and collinear overlap
+fn segments_intersect(
+    p1: &LayoutPosition,
+    p2: &LayoutPosition,
+    q1: &LayoutPosition,
+    q2: &LayoutPosition,
+) -> bool {
+    let orientation = |a: &LayoutPosition, b: &LayoutPosition, c: &LayoutPosition| {
+        let cross = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+        if cross.abs() < 1e-9 {
+            0
+        } else if cross > 0.0 {
+            1
+        } else {
+            -1
+        }
+    };
+    let on_segment = |a: &LayoutPosition, b: &LayoutPosition, c: &LayoutPosition| {
+        c.x >= a.x.min(b.x) && c.x <= a.x.max(b.x) && c.y >= a.y.min(b.y) && c.y <= a.y.max(b.y)
+    };
+
+    let (o1, o2) = (orientation(p1, p2, q1), orientation(p1, p2, q2));
+    let (o3, o4) = (orientation(q1, q2, p1), orientation(q1, q2, p2));
+    if o1 != o2 && o3 != o4 {
+        return true;
+    }
+    (o1 == 0 && on_segment(p1, p2, q1))
+        || (o2 == 0 && on_segment(p1, p2, q2))
+        || (o3 == 0 && on_segment(q1, q2, p1))
+        || (o4 == 0 && on_segment(q1, q2, p2))
+}
+
+/// Distribution of straight-line door-to-door corridor lengths
+pub fn connection_length_stats(layout: &DungeonLayout) -> DistributionStats {
+    let lengths: Vec<f64> = layout
+        .connections
+        .iter()
+        .map(|c| {
+            ((c.to_door.x - c.from_door.x).powi(2) + (c.to_door.y - c.from_door.y).powi(2)).sqrt()
+        })
+        .collect();
+    calculate_stats(&lengths)
+}
+
+/// Spawn points bucketed by `spawn_type`, preserving layout order within each bucket
+pub fn spawns_by_type(layout: &DungeonLayout) -> HashMap<String, Vec<SpawnPoint>> {
+    let mut groups: HashMap<String, Vec<SpawnPoint>> = HashMap::new();
+    for spawn in &layout.spawn_points {
+        groups
+            .entry(spawn.spawn_type.clone())
+            .or_default()
+            .push(spawn.clone());
+    }
+    groups
+}
+
+/// Number of connections touching each room; rooms without doors map to 0
+pub fn door_counts(layout: &DungeonLayout) -> HashMap<String, u32> {
+    let mut counts: HashMap<String, u32> = layout.rooms.iter().map(|r| (r.id.clone(), 0)).collect();
+    for conn in &layout.connections {
+        *counts.entry(conn.from_room_id.clone()).or_default() += 1;
+        if conn.to_room_id != conn.from_room_id {
+            *counts.entry(conn.to_room_id.clone()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Fraction of rooms holding at least one entity or spawn point, in 0..1
+pub fn populated_fraction(layout: &DungeonLayout) -> f64 {
+    if layout.rooms.is_empty() {
+        return 0.0;
+    }
+    let populated = layout
+        .rooms
+        .iter()
+        .filter(|room| {
+            !room.entities.is_empty() || layout.spawn_points.iter().any(|s| s.room_id == room.id)
+        })
+        .count();
+    populated as f64 / layout.rooms.len() as f64
+}
+
+/// Gini coefficient of `loot` entity counts per room.
+///
+/// 0 when every room holds the same amount of loot, 1 when all of it sits in a
+/// single room. The coefficient is scaled by `n / (n - 1)` so that extreme is
+/// reachable with few rooms. Layouts without loot or with fewer than two rooms score 0.
+pub fn loot_distribution_evenness(layout: &DungeonLayout) -> f64 {
+    let mut counts: Vec<f64> = layout
+        .rooms
+        .iter()
+        .map(|r| {
+            r.entities
+                .iter()
+                .filter(|e| e.entity_type == "loot")
+                .count() as f64
+        })
+        .collect();
+    let n = counts.len() as f64;
+    let total: f64 = counts.iter().sum();
+    if counts.len() < 2 || total == 0.0 {
+        return 0.0;
+    }
+
+    counts.sort_by(f64::total_cmp);
+    let ranked: f64 = counts
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i + 1) as f64 * c)
+        .sum();
+    let gini = 2.0 * ranked / (n * total) - (n + 1.0) / n;
+    gini * n / (n - 1.0)
+}
+
+/// Room centers averaged with each room weighted by its entity count.
+///
+/// Falls back to the plain average of room centers when no room has entities.
+pub fn weighted_centroid(layout: &DungeonLayout) -> LayoutPosition {
+    let total: usize = layout.rooms.iter().map(|r| r.entities.len()).sum();
+    let weight = |entities: usize| if total == 0 { 1.0 } else { entities as f64 };
+
+    let (mut x, mut y, mut weights) = (0.0, 0.0, 0.0);
+    for room in &layout.rooms {
+        let w = weight(room.entities.len());
+        let center = RoomGenerator::get_center(room);
+        x += center.x * w;
+        y += center.y * w;
+        weights += w;
+    }
+
+    if weights == 0.0 {
+        return LayoutPosition { x: 0.0, y: 0.0 };
+    }
+    LayoutPosition {
+        x: x / weights,
+        y: y / weights,
+    }
+}
+
+/// Every distinct tag found in room metadata `tags` arrays, sorted
+pub fn tags_in_use(layout: &DungeonLayout) -> Vec<String> {
+    let tags: std::collections::BTreeSet<String> = layout
+        .rooms
+        .iter()
+        .filter_map(|r| r.metadata.get("tags").and_then(|v| v.as_array()))
+        .flatten()
+        .filter_map(|t| t.as_str().map(String::from))
+        .collect();
+    tags.into_iter().collect()
+}
+
+/// Mean `(width, height)` of the rooms of each `room_type`
+pub fn room_size_by_type(layout: &DungeonLayout) -> HashMap<String, (f64, f64)> {
+    let mut totals: HashMap<String, (f64, f64, u32)> = HashMap::new();
+    for room in &layout.rooms {
+        let entry = totals.entry(room.room_type.clone()).or_default();
+        entry.0 += room.bounds.width;
+        entry.1 += room.bounds.height;
+        entry.2 += 1;
+    }
+    totals
+        .into_iter()
+        .map(|(room_type, (width, height, count))| {
+            (room_type, (width / count as f64, height / count as f64))
+        })
+        .collect()
+}
+
+/// Smallest rectangle enclosing every room, or `None` for an empty layout
+pub fn layout_bounds(layout: &DungeonLayout) -> Option<Rectangle> {
+    let first = layout.rooms.first()?;
+    let (mut min_x, mut min_y) = (first.bounds.x, first.bounds.y);
+    let (mut max_x, mut max_y) = (min_x + first.bounds.width, min_y + first.bounds.height);
+    for room in &layout.rooms[1..] {
+        min_x = min_x.min(room.bounds.x);
+        min_y = min_y.min(room.bounds.y);
+        max_x = max_x.max(room.bounds.x + room.bounds.width);
+        max_y = max_y.max(room.bounds.y + room.bounds.height);
+    }
+    Some(Rectangle {
+        x: min_x,
+        y: min_y,
+        width: max_x - min_x,
+        height: max_y - min_y,
+    })
+}
+
+/// Total room area divided by the area of the layout's bounding box.
+///
+/// Near 1 for tightly packed dungeons, near 0 for sprawling ones. Empty layouts
+/// and degenerate (zero-area) bounds score 0; overlapping rooms can push it above 1.
+pub fn compactness(layout: &DungeonLayout) -> f64 {
+    let Some(bounds) = layout_bounds(layout) else {
+        return 0.0;
+    };
+    let bounds_area = bounds.width * bounds.height;
+    if bounds_area <= 0.0 {
+        return 0.0;
+    }
+    let room_area: f64 = layout
+        .rooms
+        .iter()
+        .map(|r| r.bounds.width * r.bounds.height)
+        .sum();
+    room_area / bounds_area
+}
+
+/// Cells across every room's tile grid, for sizing an engine's tilemap.
+///
+/// Rooms without `tiles` count the grid `RoomGenerator::generate_tiles` would
+/// produce: one cell per unit of their rounded bounds.
+pub fn total_tiles(layout: &DungeonLayout) -> u64 {
+    layout
+        .rooms
+        .iter()
+        .map(|room| match &room.tiles {
+            Some(tiles) => tiles.iter().map(|row| row.len() as u64).sum(),
+            None => {
+                let width = room.bounds.width.round().max(0.0) as u64;
+                let height = room.bounds.height.round().max(0.0) as u64;
+                width * height
+            }
+        })
+        .sum()
+}
+
+/// Per-room camera rectangle: the room grown by `margin` on every side, clipped to the layout bounds
+pub fn camera_bounds(layout: &DungeonLayout, margin: f64) -> HashMap<String, Rectangle> {
+    let Some(outer) = layout_bounds(layout) else {
+        return HashMap::new();
+    };
+    let (outer_right, outer_bottom) = (outer.x + outer.width, outer.y + outer.height);
+
+    layout
+        .rooms
+        .iter()
+        .map(|room| {
+            let b = &room.bounds;
+            let left = (b.x - margin).max(outer.x);
+            let top = (b.y - margin).max(outer.y);
+            let right = (b.x + b.width + margin).min(outer_right);
+            let bottom = (b.y + b.height + margin).min(outer_bottom);
+            let camera = Rectangle {
+                x: left,
+                y: top,
+                width: right - left,
+                height: bottom - top,
+            };
+            (room.id.clone(), camera)
+        })
+        .collect()
+}
+
+/// Fraction of each cell covered by rooms on a `resolution` x `resolution` grid over the layout bounds
+pub fn density_grid(layout: &DungeonLayout, resolution: usize) -> Vec<Vec<f64>> {
+    let bounds = match layout_bounds(layout) {
+        Some(b) if resolution > 0 && b.width > 0.0 && b.height > 0.0 => b,
+        _ => return vec![],
+    };
+    let cell_w = bounds.width / resolution as f64;
+    let cell_h = bounds.height / resolution as f64;
+
+    (0..resolution)
+        .map(|row| {
+            (0..resolution)
+                .map(|col| {
+                    let cell_x = bounds.x + col as f64 * cell_w;
+                    let cell_y = bounds.y + row as f64 * cell_h;
+                    let covered: f64 = layout
+                        .rooms
+                        .iter()
+                        .map(|r| {
+                            let w = (r.bounds.x + r.bounds.width).min(cell_x + cell_w)
+                                - r.bounds.x.max(cell_x);
+                            let h = (r.bounds.y + r.bounds.height).min(cell_y + cell_h)
+                                - r.bounds.y.max(cell_y);
+                            w.max(0.0) * h.max(0.0)
+                        })
+                        .sum();
+                    (covered / (cell_w * cell_h)).min(1.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Average pairwise structural distance across a batch of layouts, in 0..1.
+///
+/// Each pair is compared on room count, room-type mix and connection topology
+/// (degree distribution); a batch of identical layouts scores 0.
+pub fn batch_diversity(layouts: &[&DungeonLayout]) -> f64 {
+    if layouts.len() < 2 {
+        return 0.0;
+    }
+
+    let mut total = 0.0;
+    let mut pairs = 0u32;
+    for i in 0..layouts.len() {
+        for j in (i + 1)..layouts.len() {
+            total += structural_distance(layouts[i], layouts[j]);
+            pairs += 1;
+        }
+    }
+
+    total / pairs as f64
+}
+
+fn structural_distance(a: &DungeonLayout, b: &DungeonLayout) -> f64 {
+    let (na, nb) = (a.rooms.len(), b.rooms.len());
+    if na == 0 && nb == 0 {
+        return 0.0;
+    }
+
+    let room_count = na.abs_diff(nb) as f64 / na.max(nb) as f64;
+
+    let type_counts = |layout: &DungeonLayout| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for room in &layout.rooms {
+            *counts.entry(room.room_type.clone()).or_default() += 1;
+        }
+        counts
+    };
+    let room_types = histogram_distance(&type_counts(a), &type_counts(b), na + nb);
+
+    let degree_counts = |layout: &DungeonLayout| {
+        let mut degrees: HashMap<String, usize> = HashMap::new();
+        for conn in &layout.connections {
+            *degrees.entry(conn.from_room_id.clone()).or_default() += 1;
+            *degrees.entry(conn.to_room_id.clone()).or_default() += 1;
+        }
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for room in &layout.rooms {
+            *counts
+                .entry(degrees.get(&room.id).copied().unwrap_or(0))
+                .or_default() += 1;
+        }
+        counts
+    };
+    let topology = histogram_distance(&degree_counts(a), &degree_counts(b), na + nb);
+
+    (room_count + room_types + topology) / 3.0
+}
+
+/// Normalized L1 distance between two count histograms sharing a total of `total` items
+fn histogram_distance<K: std::hash::Hash + Eq>(
+    a: &HashMap<K, usize>,
+    b: &HashMap<K, usize>,
+    total: usize,
+) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let mut diff = 0;
+    for (key, count) in a {
+        diff += count.abs_diff(b.get(key).copied().unwrap_or(0));
+    }
+    for (key, count) in b {
+        if !a.contains_key(key) {
+            diff += count;
+        }
+    }
+    diff as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GeneratedRoom;
+    use std::collections::HashMap;
+
+    fn room(id: &str, room_type: &str, x: f64, y: f64, width: f64, height: f64) -> GeneratedRoom {
+        GeneratedRoom {
+            id: id.to_string(),
+            room_type: room_type.to_string(),
+            bounds: Rectangle {
+                x,
+                y,
+                width,
+                height,
+            },
+            tiles: None,
+            entities: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn connect(from: &GeneratedRoom, to: &GeneratedRoom) -> RoomConnection {
+        RoomConnection {
+            from_room_id: from.id.clone(),
+            to_room_id: to.id.clone(),
+            from_door: LayoutPosition {
+                x: from.bounds.x + from.bounds.width / 2.0,
+                y: from.bounds.y + from.bounds.height / 2.0,
+            },
+            to_door: LayoutPosition {
+                x: to.bounds.x + to.bounds.width / 2.0,
+                y: to.bounds.y + to.bounds.height / 2.0,
+            },
+        }
+    }
+
+    /// Build a layout from rooms and `(from, to)` index pairs, starting in the first room
+    fn layout(rooms: Vec<GeneratedRoom>, links: &[(usize, usize)]) -> DungeonLayout {
+        let connections = links
+            .iter()
+            .map(|&(a, b)| connect(&rooms[a], &rooms[b]))
+            .collect();
+        let player_start = LayoutPosition {
+            x: rooms[0].bounds.x + 1.0,
+            y: rooms[0].bounds.y + 1.0,
+        };
+        DungeonLayout {
+            rooms,
+            connections,
+            spawn_points: vec![],
+            player_start,
+            exits: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_reachable_area_fully_connected() {
+        let rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "default", 20.0, 0.0, 10.0, 10.0),
+        ];
+        let layout = layout(rooms, &[(0, 1)]);
+
+        assert!((reachable_area_fraction(&layout) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reachable_area_with_isolated_room() {
+        let rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "default", 20.0, 0.0, 10.0, 10.0),
+            room("isolated", "default", 50.0, 50.0, 10.0, 10.0),
+        ];
+        let layout = layout(rooms, &[(0, 1)]);
+
+        let fraction = reachable_area_fraction(&layout);
+        assert!(fraction < 1.0);
+        assert!((fraction - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_batch_diversity_identical_layouts_score_zero() {
+        let rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "boss", 20.0, 0.0, 10.0, 10.0),
+        ];
+        let base = layout(rooms, &[(0, 1)]);
+        let batch = vec![&base, &base, &base];
+
+        assert_eq!(batch_diversity(&batch), 0.0);
+    }
+
+    #[test]
+    fn test_batch_diversity_varied_layouts_score_higher() {
+        let small = layout(
+            vec![
+                room("a", "start", 0.0, 0.0, 10.0, 10.0),
+                room("b", "boss", 20.0, 0.0, 10.0, 10.0),
+            ],
+            &[(0, 1)],
+        );
+        let large = layout(
+            vec![
+                room("a", "start", 0.0, 0.0, 10.0, 10.0),
+                room("b", "treasure", 20.0, 0.0, 10.0, 10.0),
+                room("c", "shop", 0.0, 20.0, 10.0, 10.0),
+                room("d", "boss", 20.0, 20.0, 10.0, 10.0),
+            ],
+            &[(0, 1), (0, 2), (0, 3)],
+        );
+
+        let diversity = batch_diversity(&[&small, &large]);
+        assert!(diversity > 0.0);
+        assert!(diversity <= 1.0);
+    }
+
+    #[test]
+    fn test_longest_dead_end() {
+        // Critical path a-b-c; branch of two rooms off a, branch of three rooms off b
+        let rooms = vec![
+            room("a", "start", 0.0, 0.0, 5.0, 5.0),
+            room("b", "default", 10.0, 0.0, 5.0, 5.0),
+            room("c", "boss", 20.0, 0.0, 5.0, 5.0),
+            room("a1", "default", 0.0, 10.0, 5.0, 5.0),
+            room("a2", "default", 0.0, 20.0, 5.0, 5.0),
+            room("b1", "default", 10.0, 10.0, 5.0, 5.0),
+            room("b2", "default", 10.0, 20.0, 5.0, 5.0),
+            room("b3", "default", 10.0, 30.0, 5.0, 5.0),
+        ];
+        let mut layout = layout(
+            rooms,
+            &[(0, 1), (1, 2), (0, 3), (3, 4), (1, 5), (5, 6), (6, 7)],
+        );
+        layout.exits = vec![LayoutPosition { x: 22.0, y: 2.0 }];
+
+        assert_eq!(longest_dead_end(&layout), 3);
+    }
+
+    #[test]
+    fn test_count_transitions_accumulates_across_layouts() {
+        let sequence = layout(
+            vec![
+                room("a", "start", 0.0, 0.0, 5.0, 5.0),
+                room("b", "combat", 10.0, 0.0, 5.0, 5.0),
+                room("c", "treasure", 20.0, 0.0, 5.0, 5.0),
+                room("d", "combat", 30.0, 0.0, 5.0, 5.0),
+                room("e", "boss", 40.0, 0.0, 5.0, 5.0),
+            ],
+            &[(0, 1), (1, 2), (2, 3), (3, 4)],
+        );
+
+        let mut matrix = HashMap::new();
+        count_transitions(&sequence, &mut matrix);
+        count_transitions(&sequence, &mut matrix);
+
+        assert_eq!(matrix["start"]["combat"], 2);
+        assert_eq!(matrix["combat"]["treasure"], 2);
+        assert_eq!(matrix["treasure"]["combat"], 2);
+        assert_eq!(matrix["combat"]["boss"], 2);
+        assert_eq!(matrix["combat"].len(), 2);
+    }
+
+    #[test]
+    fn test_shannon_entropy() {
+        assert_eq!(shannon_entropy(&[6, 6, 6, 6]), 0.0);
+        assert!((shannon_entropy(&[4, 5, 6, 7]) - 2.0).abs() < 1e-9);
+        assert!(shannon_entropy(&[4, 4, 5, 6]) > 0.0);
+    }
+
+    #[test]
+    fn test_spawns_by_type_groups_mixed_spawns() {
+        let mut layout = layout(vec![room("a", "start", 0.0, 0.0, 10.0, 10.0)], &[]);
+        for (i, spawn_type) in ["enemy", "item", "enemy", "npc", "enemy"]
+            .iter()
+            .enumerate()
+        {
+            layout.spawn_points.push(SpawnPoint {
+                id: format!("spawn_{}", i),
+                spawn_type: spawn_type.to_string(),
+                position: LayoutPosition { x: 1.0, y: 1.0 },
+                room_id: "a".to_string(),
+            });
+        }
+
+        let groups = spawns_by_type(&layout);
+
+        assert_eq!(groups.len(), 3);
+        let enemy_ids: Vec<&str> = groups["enemy"].iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(enemy_ids, vec!["spawn_0", "spawn_2", "spawn_4"]);
+        assert_eq!(groups["item"].len(), 1);
+        assert_eq!(groups["npc"].len(), 1);
+    }
+
+    #[test]
+    fn test_door_counts_hub_room() {
+        // "hub" connects to every other room; "lonely" has no doors
+        let rooms = vec![
+            room("hub", "start", 0.0, 0.0, 10.0, 10.0),
+            room("a", "default", 20.0, 0.0, 10.0, 10.0),
+            room("b", "default", 0.0, 20.0, 10.0, 10.0),
+            room("c", "default", -20.0, 0.0, 10.0, 10.0),
+            room("lonely", "default", 50.0, 50.0, 10.0, 10.0),
+        ];
+        let layout = layout(rooms, &[(0, 1), (0, 2), (3, 0), (1, 2)]);
+
+        let counts = door_counts(&layout);
+        let hub_connections = layout
+            .connections
+            .iter()
+            .filter(|c| c.from_room_id == "hub" || c.to_room_id == "hub")
+            .count() as u32;
+
+        assert_eq!(counts["hub"], hub_connections);
+        assert_eq!(counts["hub"], 3);
+        assert_eq!(counts["a"], 2);
+        assert_eq!(counts["c"], 1);
+        assert_eq!(counts["lonely"], 0);
+    }
+
+    #[test]
+    fn test_room_size_by_type_averages_mixed_types() {
+        let rooms = vec![
+            room("a", "default", 0.0, 0.0, 10.0, 6.0),
+            room("b", "default", 20.0, 0.0, 6.0, 8.0),
+            room("c", "boss", 40.0, 0.0, 20.0, 15.0),
+        ];
+        let layout = layout(rooms, &[(0, 1), (1, 2)]);
+
+        let sizes = room_size_by_type(&layout);
+
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes["default"], (8.0, 7.0));
+        assert_eq!(sizes["boss"], (20.0, 15.0));
+    }
+
+    #[test]
+    fn test_populated_fraction_counts_entities_and_spawns() {
+        let mut rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "corridor", 20.0, 0.0, 10.0, 4.0),
+            room("c", "treasure", 40.0, 0.0, 10.0, 10.0),
+            room("d", "corridor", 60.0, 0.0, 10.0, 4.0),
+        ];
+        rooms[2].entities.push(crate::models::PlacedEntity {
+            id: "chest".to_string(),
+            entity_type: "loot".to_string(),
+            position: LayoutPosition { x: 45.0, y: 5.0 },
+            rotation: None,
+            metadata: HashMap::new(),
+        });
+        let mut layout = layout(rooms, &[(0, 1), (1, 2), (2, 3)]);
+        layout.spawn_points.push(SpawnPoint {
+            id: "spawn_0".to_string(),
+            spawn_type: "enemy".to_string(),
+            position: LayoutPosition { x: 5.0, y: 5.0 },
+            room_id: "a".to_string(),
+        });
+
+        // "a" has a spawn and "c" an entity; both corridors are empty
+        assert!((populated_fraction(&layout) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weighted_centroid_shifts_toward_entities() {
+        let mut rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "combat", 40.0, 0.0, 10.0, 10.0),
+        ];
+        let plain = weighted_centroid(&layout(rooms.clone(), &[(0, 1)]));
+        assert!((plain.x - 25.0).abs() < 1e-9);
+
+        for i in 0..3 {
+            rooms[1].entities.push(crate::models::PlacedEntity {
+                id: format!("enemy_{}", i),
+                entity_type: "enemy".to_string(),
+                position: LayoutPosition { x: 45.0, y: 5.0 },
+                rotation: None,
+                metadata: HashMap::new(),
+            });
+        }
+        rooms[0].entities.push(crate::models::PlacedEntity {
+            id: "chest".to_string(),
+            entity_type: "loot".to_string(),
+            position: LayoutPosition { x: 5.0, y: 5.0 },
+            rotation: None,
+            metadata: HashMap::new(),
+        });
+
+        // Weights 1:3 put the centroid three quarters of the way to "b"
+        let weighted = weighted_centroid(&layout(rooms, &[(0, 1)]));
+        assert!((weighted.x - 35.0).abs() < 1e-9);
+        assert!((weighted.y - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_crossing_connections_reports_crossing_pair() {
+        // Four rooms on the corners of a square, linked diagonally in an X
+        let rooms = vec![
+            room("nw", "default", 0.0, 0.0, 4.0, 4.0),
+            room("ne", "default", 20.0, 0.0, 4.0, 4.0),
+            room("sw", "default", 0.0, 20.0, 4.0, 4.0),
+            room("se", "default", 20.0, 20.0, 4.0, 4.0),
+        ];
+        let crossing = layout(rooms.clone(), &[(0, 3), (1, 2)]);
+        let parallel = layout(rooms, &[(0, 1), (2, 3)]);
+
+        assert_eq!(
+            crossing_connections(&crossing),
+            vec![("nw->se".to_string(), "ne->sw".to_string())]
+        );
+        assert!(crossing_connections(&parallel).is_empty());
+    }
+
+    #[test]
+    fn test_backtrack_ratio_linear_versus_branchy() {
+        let line = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "default", 20.0, 0.0, 10.0, 10.0),
+            room("c", "default", 40.0, 0.0, 10.0, 10.0),
+            room("d", "boss", 60.0, 0.0, 10.0, 10.0),
+        ];
+        let linear = layout(line.clone(), &[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(backtrack_ratio(&linear), 0.0);
+
+        // "b" is a hub with two dead-end side rooms before the exit "d"
+        let mut rooms = line;
+        rooms.push(room("e", "default", 20.0, 20.0, 10.0, 10.0));
+        rooms.push(room("f", "default", 20.0, -20.0, 10.0, 10.0));
+        let mut branchy = layout(rooms, &[(0, 1), (1, 2), (2, 3), (1, 4), (1, 5)]);
+        branchy.exits = vec![LayoutPosition { x: 65.0, y: 5.0 }];
+
+        // a b e b f b c d: 7 steps, 2 of them back into "b"
+        assert!((backtrack_ratio(&branchy) - 2.0 / 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_difficulty_rating_empty_versus_crowded() {
+        let empty = DungeonLayout {
+            rooms: vec![],
+            connections: vec![],
+            spawn_points: vec![],
+            player_start: LayoutPosition { x: 0.0, y: 0.0 },
+            exits: vec![],
+            metadata: HashMap::new(),
+        };
+        assert_eq!(difficulty_rating(&empty, &DifficultyWeights::default()), 1);
+
+        // A 20-room spine with a side room off every other spine room, ten enemies each
+        let mut rooms: Vec<GeneratedRoom> = (0..20)
+            .map(|i| {
+                room(
+                    &format!("m{}", i),
+                    "combat",
+                    i as f64 * 20.0,
+                    0.0,
+                    10.0,
+                    10.0,
+                )
+            })
+            .collect();
+        let mut links: Vec<(usize, usize)> = (0..19).map(|i| (i, i + 1)).collect();
+        for i in (1..19).step_by(2) {
+            rooms.push(room(
+                &format!("s{}", i),
+                "combat",
+                i as f64 * 20.0,
+                20.0,
+                10.0,
+                10.0,
+            ));
+            links.push((i, rooms.len() - 1));
+        }
+        let mut crowded = layout(rooms, &links);
+        crowded.exits = vec![LayoutPosition { x: 385.0, y: 5.0 }];
+        for room in &crowded.rooms {
+            for i in 0..10 {
+                crowded.spawn_points.push(SpawnPoint {
+                    id: format!("{}_{}", room.id, i),
+                    spawn_type: "enemy".to_string(),
+                    position: LayoutPosition {
+                        x: room.bounds.x + 5.0,
+                        y: room.bounds.y + 5.0,
+                    },
+                    room_id: room.id.clone(),
+                });
+            }
+        }
+
+        assert!(difficulty_rating(&crowded, &DifficultyWeights::default()) >= 8);
+    }
+
+    #[test]
+    fn test_tags_in_use_collects_distinct_tags() {
+        let mut rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "default", 20.0, 0.0, 10.0, 10.0),
+            room("c", "boss", 40.0, 0.0, 10.0, 10.0),
+        ];
+        rooms[0]
+            .metadata
+            .insert("tags".to_string(), serde_json::json!(["safe", "lit"]));
+        rooms[2].metadata.insert(
+            "tags".to_string(),
+            serde_json::json!(["lit", "boss", "dark"]),
+        );
+
+        assert_eq!(
+            tags_in_use(&layout(rooms, &[(0, 1), (1, 2)])),
+            vec!["boss", "dark", "lit", "safe"]
+        );
+    }
+
+    #[test]
+    fn test_camera_bounds_add_margin_within_layout() {
+        let rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "default", 20.0, 5.0, 10.0, 10.0),
+            room("c", "boss", 40.0, 0.0, 20.0, 30.0),
+        ];
+        let layout = layout(rooms, &[(0, 1), (1, 2)]);
+        let outer = layout_bounds(&layout).unwrap();
+
+        let cameras = camera_bounds(&layout, 3.0);
+
+        // "b" sits inside the layout, so it gets the full margin on every side
+        let b = &cameras["b"];
+        assert_eq!((b.x, b.y, b.width, b.height), (17.0, 2.0, 16.0, 16.0));
+        // "a" touches the top-left layout corner and is clipped there
+        let a = &cameras["a"];
+        assert_eq!((a.x, a.y, a.width, a.height), (0.0, 0.0, 13.0, 13.0));
+        for (id, camera) in &cameras {
+            let room = layout.rooms.iter().find(|r| &r.id == id).unwrap();
+            assert!(camera.x <= room.bounds.x && camera.y <= room.bounds.y);
+            assert!(camera.x >= outer.x && camera.y >= outer.y);
+            assert!(camera.x + camera.width <= outer.x + outer.width);
+            assert!(camera.y + camera.height <= outer.y + outer.height);
+        }
+    }
+
+    #[test]
+    fn test_connection_length_stats_mean() {
+        // Doors sit at room centers: a->b spans 20 units, b->c spans 30 (3-4-5 triangle scaled by 6)
+        let rooms = vec![
+            room("a", "start", 0.0, 0.0, 10.0, 10.0),
+            room("b", "default", 20.0, 0.0, 10.0, 10.0),
+            room("c", "default", 38.0, 24.0, 10.0, 10.0),
+        ];
+        let stats = connection_length_stats(&layout(rooms, &[(0, 1), (1, 2)]));
+
+        assert!((stats.mean - 25.0).abs() < 1e-9);
+        assert_eq!(stats.min, 20.0);
+        assert_eq!(stats.max, 30.0);
+    }
+
+    #[test]
+    fn test_compactness_dense_and_sparse() {
+        let single = layout(vec![room("a", "start", 0.0, 0.0, 10.0, 10.0)], &[]);
+        assert!((compactness(&single) - 1.0).abs() < 1e-9);
+
+        let sparse = layout(
+            vec![
+                room("a", "start", 0.0, 0.0, 10.0, 10.0),
+                room("b", "exit", 190.0, 190.0, 10.0, 10.0),
+            ],
+            &[(0, 1)],
+        );
+        assert!(compactness(&sparse) < 0.01);
+
+        let flat = layout(vec![room("a", "start", 0.0, 0.0, 10.0, 0.0)], &[]);
+        assert_eq!(compactness(&flat), 0.0);
+    }
+
+    #[test]
+    fn test_loot_distribution_evenness() {
+        let chest = |id: usize| crate::models::PlacedEntity {
+            id: format!("chest_{}", id),
+            entity_type: "loot".to_string(),
+            position: LayoutPosition { x: 0.0, y: 0.0 },
+            rotation: None,
+            metadata: HashMap::new(),
+        };
+        let rooms: Vec<GeneratedRoom> = (0..4)
+            .map(|i| {
+                room(
+                    &format!("r{}", i),
+                    "default",
+                    i as f64 * 20.0,
+                    0.0,
+                    10.0,
+                    10.0,
+                )
+            })
+            .collect();
+
+        let mut even = rooms.clone();
+        for (i, room) in even.iter_mut().enumerate() {
+            room.entities.push(chest(i));
+        }
+        assert!(loot_distribution_evenness(&layout(even, &[])) < 1e-9);
+
+        let mut concentrated = rooms;
+        for i in 0..4 {
+            concentrated[2].entities.push(chest(i));
+        }
+        assert!((loot_distribution_evenness(&layout(concentrated, &[])) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tour_order_visits_each_room_once_from_start() {
+        // a - b - c with a side branch b - d, plus an isolated room e
+        let mut layout = layout(
+            vec![
+                room("a", "start", 0.0, 0.0, 10.0, 10.0),
+                room("b", "default", 20.0, 0.0, 10.0, 10.0),
+                room("c", "exit", 40.0, 0.0, 10.0, 10.0),
+                room("d", "default", 20.0, 20.0, 10.0, 10.0),
+                room("e", "default", 80.0, 80.0, 10.0, 10.0),
+            ],
+            &[(0, 1), (1, 2), (1, 3)],
+        );
+        layout.exits = vec![RoomGenerator::get_center(&layout.rooms[2])];
+
+        let order = tour_order(&layout);
+
+        assert_eq!(order, ["a", "b", "d", "c", "e"]);
+    }
+
+    #[test]
+    fn test_total_tiles_sums_grids_and_rasterized_bounds() {
+        let mut tiled = room("a", "start", 0.0, 0.0, 4.0, 3.0);
+        tiled.tiles = Some(vec![vec![0; 4]; 3]);
+        let layout = layout(
+            vec![tiled, room("b", "exit", 10.0, 0.0, 6.0, 5.4)],
+            &[(0, 1)],
+        );
+
+        assert_eq!(total_tiles(&layout), 4 * 3 + 6 * 5);
+    }
+
+    #[test]
+    fn test_linearity_of_chain_and_star() {
+        let chain_rooms = (0..5)
+            .map(|i| {
+                room(
+                    &format!("c{}", i),
+                    "default",
+                    i as f64 * 20.0,
+                    0.0,
+                    10.0,
+                    10.0,
+                )
+            })
+            .collect();
+        let chain = layout(chain_rooms, &[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert!((linearity(&chain) - 1.0).abs() < 1e-9);
+
+        let mut star_rooms = vec![room("hub", "start", 0.0, 0.0, 10.0, 10.0)];
+        star_rooms.extend((0..10).map(|i| {
+            room(
+                &format!("s{}", i),
+                "default",
+                i as f64 * 20.0,
+                40.0,
+                10.0,
+                10.0,
+            )
+        }));
+        let spokes: Vec<(usize, usize)> = (1..=10).map(|i| (0, i)).collect();
+        let star = layout(star_rooms, &spokes);
+        assert!((linearity(&star) - 3.0 / 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mean_depth_of_chain_ignores_unreachable_rooms() {
+        let mut rooms: Vec<GeneratedRoom> = (0..6)
+            .map(|i| {
+                room(
+                    &format!("c{}", i),
+                    "default",
+                    i as f64 * 20.0,
+                    0.0,
+                    10.0,
+                    10.0,
+                )
+            })
+            .collect();
+        rooms.push(room("island", "default", 0.0, 100.0, 10.0, 10.0));
+        let chain = layout(rooms, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
+
+        assert!((mean_depth(&chain) - 5.0 / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_chokepoints_of_dumbbell() {
+        // Two triangles joined through a single bridge room
+        let rooms: Vec<GeneratedRoom> = (0..7)
+            .map(|i| {
+                room(
+                    &format!("r{}", i),
+                    "default",
+                    i as f64 * 20.0,
+                    0.0,
+                    10.0,
+                    10.0,
+                )
+            })
+            .collect();
+        let dumbbell = layout(
+            rooms,
+            &[
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 6),
+                (6, 4),
+            ],
+        );
+
+        assert_eq!(chokepoints(&dumbbell), vec!["r2", "r3", "r4"]);
+    }
+}
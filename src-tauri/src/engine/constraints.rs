@@ -0,0 +1,532 @@
+//! Constraint evaluation against generated layouts
+//!
+//! Constraints are checked cheapest-first, so with short-circuiting enabled an
+//! inexpensive failure (a room count) skips costlier checks (all-pairs distances).
+
+use super::layout_graph::LayoutGraph;
+use super::RoomGenerator;
+use crate::models::generator::{Constraint, ConstraintSeverity, ConstraintType, Generator};
+use crate::models::{ConstraintResult, DungeonLayout, GeneratedRoom};
+use std::collections::HashMap;
+
+/// Evaluate every constraint of `generator` against a layout it produced
+pub fn evaluate(generator: &Generator, layout: &DungeonLayout) -> Vec<ConstraintResult> {
+    evaluate_constraints(&generator.constraints, layout, false)
+}
+
+/// Apply the `constraintSeverity` parameter, a map of constraint id to `"error"` or `"warning"`.
+///
+/// Constraints it doesn't name, or names with an unknown severity, keep their
+/// declared severity.
+pub fn apply_severity_overrides(
+    constraints: &mut [Constraint],
+    parameters: &HashMap<String, serde_json::Value>,
+) {
+    let Some(overrides) = parameters
+        .get("constraintSeverity")
+        .and_then(|v| v.as_object())
+    else {
+        return;
+    };
+    for constraint in constraints {
+        let severity = match overrides.get(&constraint.id).and_then(|v| v.as_str()) {
+            Some("error") => ConstraintSeverity::Error,
+            Some("warning") => ConstraintSeverity::Warning,
+            _ => continue,
+        };
+        constraint.severity = severity;
+    }
+}
+
+/// Whether any failed result belongs to an Error-severity constraint
+pub fn has_error_failure(constraints: &[Constraint], results: &[ConstraintResult]) -> bool {
+    results.iter().any(|r| is_error_failure(constraints, r))
+}
+
+/// Whether `result` failed and belongs to an Error-severity constraint
+pub fn is_error_failure(constraints: &[Constraint], result: &ConstraintResult) -> bool {
+    !result.passed
+        && constraints.iter().any(|c| {
+            c.id == result.constraint_id && matches!(c.severity, ConstraintSeverity::Error)
+        })
+}
+
+/// Evaluate `constraints` against a layout in cost order.
+///
+/// With `short_circuit`, evaluation stops after the first failing Error-severity
+/// constraint; the constraints after it are left out of the results. Types
+/// without a checker yet (Required, Forbidden, Progression, Custom) are left
+/// out too, rather than reported as passed.
+pub fn evaluate_constraints(
+    constraints: &[Constraint],
+    layout: &DungeonLayout,
+    short_circuit: bool,
+) -> Vec<ConstraintResult> {
+    evaluate_with(constraints, layout, short_circuit, evaluate_constraint)
+}
+
+fn evaluate_with<F>(
+    constraints: &[Constraint],
+    layout: &DungeonLayout,
+    short_circuit: bool,
+    mut evaluate: F,
+) -> Vec<ConstraintResult>
+where
+    F: FnMut(&Constraint, &DungeonLayout) -> Option<ConstraintResult>,
+{
+    let mut ordered: Vec<&Constraint> = constraints.iter().collect();
+    // Stable sort keeps the authored order among constraints of equal cost
+    ordered.sort_by_key(|c| cost(&c.constraint_type));
+
+    let mut results = Vec::with_capacity(ordered.len());
+    for constraint in ordered {
+        let Some(result) = evaluate(constraint, layout) else {
+            continue;
+        };
+        let stop = short_circuit
+            && !result.passed
+            && matches!(constraint.severity, ConstraintSeverity::Error);
+        results.push(result);
+        if stop {
+            break;
+        }
+    }
+    results
+}
+
+/// Relative cost of checking a constraint type; lower runs first
+fn cost(constraint_type: &ConstraintType) -> u8 {
+    match constraint_type {
+        ConstraintType::Count | ConstraintType::Required | ConstraintType::Forbidden => 0,
+        ConstraintType::Density => 1,
+        ConstraintType::Connected | ConstraintType::Progression => 2,
+        ConstraintType::Distance => 3,
+        ConstraintType::Custom => 4,
+    }
+}
+
+/// Check one constraint; `None` for types that can't be evaluated yet
+fn evaluate_constraint(
+    constraint: &Constraint,
+    layout: &DungeonLayout,
+) -> Option<ConstraintResult> {
+    let outcome = match constraint.constraint_type {
+        ConstraintType::Count => check_count(constraint, layout),
+        ConstraintType::Connected => check_connected(layout),
+        ConstraintType::Distance => check_distance(constraint, layout),
+        ConstraintType::Density => check_density(constraint, layout),
+        ConstraintType::Required
+        | ConstraintType::Forbidden
+        | ConstraintType::Progression
+        | ConstraintType::Custom => return None,
+    };
+
+    Some(ConstraintResult {
+        constraint_id: constraint.id.clone(),
+        passed: outcome.is_ok(),
+        message: outcome
+            .err()
+            .map(|detail| format!("{} ({})", constraint.error_message, detail)),
+    })
+}
+
+/// `min`/`max` bounds on the number of rooms (optionally of one `roomType`),
+/// `entities` or `spawns`, selected by the `target` parameter
+fn check_count(constraint: &Constraint, layout: &DungeonLayout) -> Result<(), String> {
+    let params = &constraint.parameters;
+    let target = params
+        .get("target")
+        .and_then(|v| v.as_str())
+        .unwrap_or("rooms");
+    let count = match target {
+        "entities" => layout.rooms.iter().map(|r| r.entities.len()).sum(),
+        "spawns" => layout.spawn_points.len(),
+        _ => match params.get("roomType").and_then(|v| v.as_str()) {
+            Some(room_type) => layout
+                .rooms
+                .iter()
+                .filter(|r| r.room_type == room_type)
+                .count(),
+            None => layout.rooms.len(),
+        },
+    } as f64;
+
+    if let Some(min) = params.get("min").and_then(|v| v.as_f64()) {
+        if count < min {
+            return Err(format!("{} {} is below minimum {}", count, target, min));
+        }
+    }
+    if let Some(max) = params.get("max").and_then(|v| v.as_f64()) {
+        if count > max {
+            return Err(format!("{} {} is above maximum {}", count, target, max));
+        }
+    }
+    Ok(())
+}
+
+/// `min`/`max` bounds on entities per unit of room area, counting only
+/// `entityType` entities when that parameter is given
+fn check_density(constraint: &Constraint, layout: &DungeonLayout) -> Result<(), String> {
+    let params = &constraint.parameters;
+    let entity_type = params.get("entityType").and_then(|v| v.as_str());
+    let area: f64 = layout
+        .rooms
+        .iter()
+        .map(|r| r.bounds.width * r.bounds.height)
+        .sum();
+    let entities = layout
+        .rooms
+        .iter()
+        .flat_map(|r| &r.entities)
+        .filter(|e| entity_type.is_none_or(|t| e.entity_type == t))
+        .count();
+    let density = if area > 0.0 {
+        entities as f64 / area
+    } else {
+        0.0
+    };
+
+    if let Some(min) = params.get("min").and_then(|v| v.as_f64()) {
+        if density < min {
+            return Err(format!("density {:.4} is below minimum {}", density, min));
+        }
+    }
+    if let Some(max) = params.get("max").and_then(|v| v.as_f64()) {
+        if density > max {
+            return Err(format!("density {:.4} is above maximum {}", density, max));
+        }
+    }
+    Ok(())
+}
+
+/// Every room is reachable from the player start through connections
+fn check_connected(layout: &DungeonLayout) -> Result<(), String> {
+    let unreachable = LayoutGraph::new(layout)
+        .reachable_from_start()
+        .iter()
+        .filter(|reached| !**reached)
+        .count();
+    if unreachable == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} rooms unreachable from start", unreachable))
+    }
+}
+
+/// `minDistance`/`maxDistance` between the centers of every room matching the
+/// `from` selector and every room matching the `to` selector. A selector matches
+/// a room type or a tag; a room is never compared with itself.
+fn check_distance(constraint: &Constraint, layout: &DungeonLayout) -> Result<(), String> {
+    let params = &constraint.parameters;
+    let selector = |key: &str| params.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    let (from, to) = (selector("from"), selector("to"));
+    let min = params.get("minDistance").and_then(|v| v.as_f64());
+    let max = params.get("maxDistance").and_then(|v| v.as_f64());
+
+    let mut violations = Vec::new();
+    for a in layout.rooms.iter().filter(|r| matches_selector(r, from)) {
+        for b in layout.rooms.iter().filter(|r| matches_selector(r, to)) {
+            if a.id == b.id {
+                continue;
+            }
+            let (ca, cb) = (RoomGenerator::get_center(a), RoomGenerator::get_center(b));
+            let distance = ((ca.x - cb.x).powi(2) + (ca.y - cb.y).powi(2)).sqrt();
+            if let Some(min) = min.filter(|&min| distance < min) {
+                violations.push(format!(
+                    "{} and {} are {:.1} apart, below minimum {}",
+                    a.id, b.id, distance, min
+                ));
+            }
+            if let Some(max) = max.filter(|&max| distance > max) {
+                violations.push(format!(
+                    "{} and {} are {:.1} apart, above maximum {}",
+                    a.id, b.id, distance, max
+                ));
+            }
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations.join("; "))
+    }
+}
+
+/// Whether `selector` names the room's type or one of its tags
+fn matches_selector(room: &GeneratedRoom, selector: &str) -> bool {
+    room.room_type == selector
+        || room
+            .metadata
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(selector)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn constraint(id: &str, constraint_type: &str, parameters: serde_json::Value) -> Constraint {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "type": constraint_type,
+            "parameters": parameters,
+            "error_message": format!("{} failed", id),
+            "severity": "error",
+        }))
+        .unwrap()
+    }
+
+    fn empty_layout() -> DungeonLayout {
+        DungeonLayout {
+            rooms: vec![],
+            connections: vec![],
+            spawn_points: vec![],
+            player_start: crate::models::LayoutPosition { x: 0.0, y: 0.0 },
+            exits: vec![],
+            metadata: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_cheap_failure_short_circuits_expensive_constraint() {
+        // Authored expensive-first; evaluation must still run the count first
+        let constraints = vec![
+            constraint("far_apart", "distance", serde_json::json!({})),
+            constraint("enough_rooms", "count", serde_json::json!({ "min": 3 })),
+        ];
+        let layout = empty_layout();
+        let distance_calls = Cell::new(0);
+        let counting = |c: &Constraint, l: &DungeonLayout| {
+            if c.id == "far_apart" {
+                distance_calls.set(distance_calls.get() + 1);
+            }
+            evaluate_constraint(c, l)
+        };
+
+        let results = evaluate_with(&constraints, &layout, true, counting);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].constraint_id, "enough_rooms");
+        assert!(!results[0].passed);
+        assert_eq!(distance_calls.get(), 0);
+
+        // Without short-circuiting every constraint runs
+        let results = evaluate_with(&constraints, &layout, false, counting);
+        assert_eq!(results.len(), 2);
+        assert_eq!(distance_calls.get(), 1);
+    }
+
+    #[test]
+    fn test_hand_edited_layout_fails_count_constraint() {
+        let mut layout = empty_layout();
+        for i in 0..3 {
+            layout.rooms.push(crate::models::GeneratedRoom {
+                id: format!("room_{}", i),
+                room_type: "default".to_string(),
+                bounds: crate::models::Rectangle {
+                    x: i as f64 * 20.0,
+                    y: 0.0,
+                    width: 10.0,
+                    height: 10.0,
+                },
+                tiles: None,
+                entities: vec![],
+                metadata: std::collections::HashMap::new(),
+            });
+        }
+        let constraints = vec![constraint(
+            "enough_rooms",
+            "count",
+            serde_json::json!({ "min": 3 }),
+        )];
+        assert!(evaluate_constraints(&constraints, &layout, false)[0].passed);
+
+        // Deleting a room in an editor drops the layout below the minimum
+        layout.rooms.pop();
+        let results = evaluate_constraints(&constraints, &layout, false);
+
+        assert!(!results[0].passed);
+        assert!(results[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with("enough_rooms failed"));
+    }
+
+    fn generator_with(constraints: Vec<Constraint>) -> Generator {
+        let mut generator: Generator = serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Generator",
+            "type": "dungeon",
+            "graph": { "nodes": [], "edges": [] }
+        }))
+        .unwrap();
+        generator.constraints = constraints;
+        generator
+    }
+
+    fn square_room(id: &str, x: f64) -> crate::models::GeneratedRoom {
+        crate::models::GeneratedRoom {
+            id: id.to_string(),
+            room_type: "default".to_string(),
+            bounds: crate::models::Rectangle {
+                x,
+                y: 0.0,
+                width: 10.0,
+                height: 10.0,
+            },
+            tiles: None,
+            entities: vec![],
+            metadata: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_disconnected_layout_fails_connected() {
+        let generator = generator_with(vec![constraint(
+            "connected",
+            "connected",
+            serde_json::json!({}),
+        )]);
+        let mut layout = empty_layout();
+        layout.player_start = crate::models::LayoutPosition { x: 5.0, y: 5.0 };
+        layout.rooms = vec![square_room("a", 0.0), square_room("b", 20.0)];
+
+        let results = evaluate(&generator, &layout);
+
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].passed);
+        assert!(has_error_failure(&generator.constraints, &results));
+    }
+
+    #[test]
+    fn test_small_dungeon_fails_count() {
+        let generator = generator_with(vec![
+            constraint("connected", "connected", serde_json::json!({})),
+            constraint("enough_rooms", "count", serde_json::json!({ "min": 5 })),
+        ]);
+        let mut layout = empty_layout();
+        layout.player_start = crate::models::LayoutPosition { x: 5.0, y: 5.0 };
+        layout.rooms = vec![square_room("a", 0.0)];
+
+        let results = evaluate(&generator, &layout);
+
+        let count = results
+            .iter()
+            .find(|r| r.constraint_id == "enough_rooms")
+            .unwrap();
+        assert!(!count.passed);
+        assert!(
+            results
+                .iter()
+                .find(|r| r.constraint_id == "connected")
+                .unwrap()
+                .passed
+        );
+    }
+
+    #[test]
+    fn test_distance_between_boss_and_start() {
+        let mut boss = square_room("boss_room", 40.0);
+        boss.room_type = "boss".to_string();
+        let mut start = square_room("entry", 0.0);
+        start.room_type = "start".to_string();
+        let mut layout = empty_layout();
+        layout.rooms = vec![start, boss];
+        let far_apart = |min: f64| {
+            constraint(
+                "boss_far",
+                "distance",
+                serde_json::json!({ "from": "boss", "to": "start", "minDistance": min }),
+            )
+        };
+
+        // Centers are 40 apart
+        let results = evaluate_constraints(&[far_apart(30.0)], &layout, false);
+        assert!(results[0].passed);
+
+        let results = evaluate_constraints(&[far_apart(50.0)], &layout, false);
+        assert!(!results[0].passed);
+        let message = results[0].message.as_deref().unwrap();
+        assert!(message.contains("boss_room") && message.contains("entry"));
+    }
+
+    #[test]
+    fn test_distance_max_matches_tags() {
+        let mut shrine = square_room("shrine", 100.0);
+        shrine
+            .metadata
+            .insert("tags".to_string(), serde_json::json!(["safe"]));
+        let mut layout = empty_layout();
+        layout.rooms = vec![square_room("a", 0.0), shrine];
+        let near = constraint(
+            "safe_nearby",
+            "distance",
+            serde_json::json!({ "from": "safe", "to": "default", "maxDistance": 60 }),
+        );
+
+        let results = evaluate_constraints(&[near], &layout, false);
+
+        assert!(!results[0].passed);
+        assert!(results[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("above maximum 60"));
+    }
+
+    #[test]
+    fn test_density_too_crowded_and_too_sparse() {
+        use rand::SeedableRng;
+
+        let mut room = square_room("a", 0.0);
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+        RoomGenerator::add_entities(&mut rng, &mut room, "enemy", 20, 20, 0.0);
+        RoomGenerator::add_entities(&mut rng, &mut room, "loot", 5, 5, 0.0);
+        let mut layout = empty_layout();
+        layout.rooms = vec![room];
+
+        // 25 entities over 100 units of area
+        let crowded = constraint("not_crowded", "density", serde_json::json!({ "max": 0.2 }));
+        let results = evaluate_constraints(&[crowded], &layout, false);
+        assert!(!results[0].passed);
+        assert!(results[0].message.as_deref().unwrap().contains("0.2500"));
+
+        // 5 loot over 100 units of area
+        let sparse = constraint(
+            "enough_loot",
+            "density",
+            serde_json::json!({ "entityType": "loot", "min": 0.1 }),
+        );
+        let results = evaluate_constraints(&[sparse], &layout, false);
+        assert!(!results[0].passed);
+        assert!(results[0].message.as_deref().unwrap().contains("0.0500"));
+
+        let enemies = constraint(
+            "enemies_ok",
+            "density",
+            serde_json::json!({ "entityType": "enemy", "min": 0.1, "max": 0.3 }),
+        );
+        assert!(evaluate_constraints(&[enemies], &layout, false)[0].passed);
+    }
+
+    #[test]
+    fn test_unevaluated_constraint_types_are_not_reported() {
+        let generator = generator_with(vec![
+            constraint(
+                "has_boss",
+                "required",
+                serde_json::json!({ "roomType": "boss" }),
+            ),
+            constraint("enough_rooms", "count", serde_json::json!({ "min": 1 })),
+        ]);
+
+        let results = evaluate(&generator, &empty_layout());
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].constraint_id, "enough_rooms");
+    }
+}
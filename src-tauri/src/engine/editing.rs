@@ -0,0 +1,573 @@
+//! Manual edits applied to an already generated layout
+
+use super::layout_graph::{self, LayoutGraph};
+use super::room_generator::{Direction, RoomConfig, RoomGenerator};
+use crate::models::{DungeonLayout, LayoutPosition, RoomConnection, SpawnPoint};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Empty space between an appended room and its neighbor
+const APPEND_SPACING: f64 = 5.0;
+
+/// Number of visual variants an entity can be assigned by `reskin_layout`
+const ENTITY_VARIANTS: u64 = 4;
+
+/// Place a new room next to `near_room_id` in `direction` and connect the two.
+///
+/// The new room is centered on its neighbor along the perpendicular axis, so a
+/// single straight corridor joins the facing walls at the neighbor's midline.
+/// Room sizes are rolled from a seed derived from the current room count,
+/// keeping repeated edits reproducible. Size ranges that can't be sampled, like
+/// min above max, are rejected.
+pub fn append_room(
+    layout: &mut DungeonLayout,
+    config: &RoomConfig,
+    near_room_id: &str,
+    direction: Direction,
+) -> Result<(), String> {
+    config.validate()?;
+    let near = layout
+        .rooms
+        .iter()
+        .find(|r| r.id == near_room_id)
+        .ok_or_else(|| format!("Room {} not found", near_room_id))?
+        .clone();
+
+    let mut rng = ChaCha8Rng::seed_from_u64(layout.rooms.len() as u64);
+    let mut room = RoomGenerator::generate(
+        &mut rng,
+        config,
+        LayoutPosition { x: 0.0, y: 0.0 },
+        &next_room_id(layout),
+    );
+
+    let center = RoomGenerator::get_center(&near);
+    let (width, height) = (room.bounds.width, room.bounds.height);
+    let (x, y) = match direction {
+        Direction::Right => (
+            near.bounds.x + near.bounds.width + APPEND_SPACING,
+            center.y - height / 2.0,
+        ),
+        Direction::Left => (
+            near.bounds.x - APPEND_SPACING - width,
+            center.y - height / 2.0,
+        ),
+        Direction::Down => (
+            center.x - width / 2.0,
+            near.bounds.y + near.bounds.height + APPEND_SPACING,
+        ),
+        Direction::Up => (
+            center.x - width / 2.0,
+            near.bounds.y - APPEND_SPACING - height,
+        ),
+    };
+    // Shape metadata (footprint, circle center) moves along with the bounds
+    let (dx, dy) = (x - room.bounds.x, y - room.bounds.y);
+    RoomGenerator::translate(&mut room, dx, dy);
+
+    let (from_door, to_door) = match direction {
+        Direction::Right => (
+            LayoutPosition {
+                x: near.bounds.x + near.bounds.width,
+                y: center.y,
+            },
+            LayoutPosition { x, y: center.y },
+        ),
+        Direction::Left => (
+            LayoutPosition {
+                x: near.bounds.x,
+                y: center.y,
+            },
+            LayoutPosition {
+                x: x + width,
+                y: center.y,
+            },
+        ),
+        Direction::Down => (
+            LayoutPosition {
+                x: center.x,
+                y: near.bounds.y + near.bounds.height,
+            },
+            LayoutPosition { x: center.x, y },
+        ),
+        Direction::Up => (
+            LayoutPosition {
+                x: center.x,
+                y: near.bounds.y,
+            },
+            LayoutPosition {
+                x: center.x,
+                y: y + height,
+            },
+        ),
+    };
+
+    layout.connections.push(RoomConnection {
+        from_room_id: near.id.clone(),
+        to_room_id: room.id.clone(),
+        from_door,
+        to_door,
+    });
+    layout.rooms.push(room);
+    Ok(())
+}
+
+/// Delete a room with its connections, entities and spawn points.
+///
+/// Former neighbors left in separate components afterwards are linked directly,
+/// reusing the doors they had toward the removed room.
+pub fn remove_room(layout: &mut DungeonLayout, room_id: &str) -> Result<(), String> {
+    let index = layout
+        .rooms
+        .iter()
+        .position(|r| r.id == room_id)
+        .ok_or_else(|| format!("Room {} not found", room_id))?;
+
+    // Each neighbor with the door it used toward the removed room, in connection order
+    let mut neighbors: Vec<(String, LayoutPosition)> = Vec::new();
+    for conn in &layout.connections {
+        let neighbor = if conn.from_room_id == room_id {
+            (conn.to_room_id.clone(), conn.to_door.clone())
+        } else if conn.to_room_id == room_id {
+            (conn.from_room_id.clone(), conn.from_door.clone())
+        } else {
+            continue;
+        };
+        if neighbor.0 != room_id && neighbors.iter().all(|(id, _)| *id != neighbor.0) {
+            neighbors.push(neighbor);
+        }
+    }
+
+    layout.rooms.remove(index);
+    layout
+        .connections
+        .retain(|c| c.from_room_id != room_id && c.to_room_id != room_id);
+    layout.spawn_points.retain(|s| s.room_id != room_id);
+
+    let Some((anchor_id, anchor_door)) = neighbors.first().cloned() else {
+        return Ok(());
+    };
+    for (neighbor_id, neighbor_door) in neighbors.iter().skip(1) {
+        if !connected(layout, &anchor_id, neighbor_id) {
+            layout.connections.push(RoomConnection {
+                from_room_id: anchor_id.clone(),
+                to_room_id: neighbor_id.clone(),
+                from_door: anchor_door.clone(),
+                to_door: neighbor_door.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn connected(layout: &DungeonLayout, a: &str, b: &str) -> bool {
+    let index_of = |id: &str| layout.rooms.iter().position(|r| r.id == id);
+    match (index_of(a), index_of(b)) {
+        (Some(a), Some(b)) => LayoutGraph::new(layout).distances_from(a)[b].is_some(),
+        _ => false,
+    }
+}
+
+/// Re-roll cosmetic attributes from `seed` while leaving all geometry untouched.
+///
+/// Rooms get a new `color` and entities a new `variant` in their metadata;
+/// entities that already face a direction get a fresh rotation. Positions,
+/// bounds, tiles and connections are never modified.
+pub fn reskin_layout(layout: &mut DungeonLayout, seed: u64) {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    for room in &mut layout.rooms {
+        let color = format!("#{:06x}", rng.gen_range(0..0x100_0000u32));
+        room.metadata
+            .insert("color".to_string(), serde_json::Value::String(color));
+
+        for entity in &mut room.entities {
+            entity.metadata.insert(
+                "variant".to_string(),
+                serde_json::json!(rng.gen_range(0..ENTITY_VARIANTS)),
+            );
+            if entity.rotation.is_some() {
+                entity.rotation = Some(rng.gen_range(0.0..std::f64::consts::TAU));
+            }
+        }
+    }
+}
+
+/// Merge spawn points closer than `min_distance` to an earlier spawn in the same room.
+///
+/// Spawns are kept in order; each one within `min_distance` of a spawn already
+/// kept in its room is dropped.
+pub fn dedupe_spawns(layout: &mut DungeonLayout, min_distance: f64) {
+    let mut kept: Vec<SpawnPoint> = Vec::with_capacity(layout.spawn_points.len());
+    for spawn in layout.spawn_points.drain(..) {
+        let duplicate = kept.iter().any(|k| {
+            let (dx, dy) = (
+                k.position.x - spawn.position.x,
+                k.position.y - spawn.position.y,
+            );
+            k.room_id == spawn.room_id && dx.hypot(dy) < min_distance
+        });
+        if !duplicate {
+            kept.push(spawn);
+        }
+    }
+    layout.spawn_points = kept;
+}
+
+/// Replace every connection with ones derived from room placement.
+///
+/// `strategy` picks which room pairs get a corridor:
+/// - `"nearest"`: each room to the room whose center is closest
+/// - `"mst"`: the minimum spanning tree over room centers, so every room is reachable
+/// - `"delaunay"`: the Delaunay triangulation of room centers, which adds loops
+///
+/// Doors go on the walls facing each other, along the axis where the centers
+/// are further apart. Door offsets are rolled from a seed derived from the room
+/// count, keeping repeated rebuilds reproducible.
+pub fn rebuild_connections(layout: &mut DungeonLayout, strategy: &str) -> Result<(), String> {
+    let centers: Vec<LayoutPosition> = layout.rooms.iter().map(RoomGenerator::get_center).collect();
+    let pairs: Vec<(usize, usize)> = match strategy.to_lowercase().as_str() {
+        "nearest" => {
+            let mut pairs: Vec<(usize, usize)> = (0..centers.len())
+                .filter_map(|i| {
+                    (0..centers.len())
+                        .filter(|&j| j != i)
+                        .min_by(|&a, &b| {
+                            distance(&centers[i], &centers[a])
+                                .total_cmp(&distance(&centers[i], &centers[b]))
+                        })
+                        .map(|j| (i.min(j), i.max(j)))
+                })
+                .collect();
+            pairs.sort_unstable();
+            pairs.dedup();
+            pairs
+        }
+        "mst" => {
+            let index_of = |id: &str| layout.rooms.iter().position(|r| r.id == id);
+            layout_graph::room_mst(layout)
+                .iter()
+                .filter_map(|(a, b, _)| Some((index_of(a)?, index_of(b)?)))
+                .collect()
+        }
+        "delaunay" => layout_graph::room_delaunay(&layout.rooms),
+        _ => return Err(format!("Unknown connection strategy: {}", strategy)),
+    };
+
+    let mut rng = ChaCha8Rng::seed_from_u64(layout.rooms.len() as u64);
+    layout.connections = pairs
+        .into_iter()
+        .map(|(a, b)| {
+            let (from, to) = (&layout.rooms[a], &layout.rooms[b]);
+            let (dx, dy) = (centers[b].x - centers[a].x, centers[b].y - centers[a].y);
+            let direction = match (dx.abs() >= dy.abs(), dx >= 0.0, dy >= 0.0) {
+                (true, true, _) => Direction::Right,
+                (true, false, _) => Direction::Left,
+                (false, _, true) => Direction::Down,
+                (false, _, false) => Direction::Up,
+            };
+            RoomConnection {
+                from_room_id: from.id.clone(),
+                to_room_id: to.id.clone(),
+                from_door: RoomGenerator::get_door_position(from, direction, &mut rng),
+                to_door: RoomGenerator::get_door_position(to, direction.opposite(), &mut rng),
+            }
+        })
+        .collect();
+    Ok(())
+}
+
+fn distance(a: &LayoutPosition, b: &LayoutPosition) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// First `room_<n>` id not already used in the layout
+fn next_room_id(layout: &DungeonLayout) -> String {
+    (layout.rooms.len()..)
+        .map(|n| format!("room_{}", n))
+        .find(|id| layout.rooms.iter().all(|r| &r.id != id))
+        .expect("unbounded id range always yields a free id")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::room_generator::RoomShape;
+    use crate::models::{GeneratedRoom, Rectangle};
+    use std::collections::HashMap;
+
+    fn single_room_layout() -> DungeonLayout {
+        DungeonLayout {
+            rooms: vec![GeneratedRoom {
+                id: "room_0".to_string(),
+                room_type: "start".to_string(),
+                bounds: Rectangle {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 10.0,
+                    height: 10.0,
+                },
+                tiles: None,
+                entities: vec![],
+                metadata: HashMap::new(),
+            }],
+            connections: vec![],
+            spawn_points: vec![],
+            player_start: LayoutPosition { x: 5.0, y: 5.0 },
+            exits: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_append_room_places_and_connects_in_direction() {
+        let mut layout = single_room_layout();
+        append_room(
+            &mut layout,
+            &RoomConfig::default(),
+            "room_0",
+            Direction::Down,
+        )
+        .unwrap();
+        append_room(
+            &mut layout,
+            &RoomConfig::default(),
+            "room_0",
+            Direction::Left,
+        )
+        .unwrap();
+
+        let below = &layout.rooms[1];
+        let left = &layout.rooms[2];
+        assert!(below.bounds.y >= 10.0);
+        assert!(left.bounds.x + left.bounds.width <= 0.0);
+
+        assert_eq!(layout.connections.len(), 2);
+        let conn = &layout.connections[0];
+        assert_eq!(conn.from_room_id, "room_0");
+        assert_eq!(conn.to_room_id, below.id);
+        // Doors share the corridor's axis and sit on the facing walls
+        assert_eq!(conn.from_door.x, conn.to_door.x);
+        assert_eq!(conn.from_door.y, 10.0);
+        assert_eq!(conn.to_door.y, below.bounds.y);
+    }
+
+    #[test]
+    fn test_append_room_moves_shape_footprint_with_room() {
+        let mut layout = single_room_layout();
+        let config = RoomConfig {
+            shape: RoomShape::Arena,
+            ..RoomConfig::default()
+        };
+
+        append_room(&mut layout, &config, "room_0", Direction::Right).unwrap();
+
+        let arena = &layout.rooms[1];
+        let bounds = &arena.bounds;
+        assert_eq!(bounds.x, 15.0);
+        let footprint = arena.metadata["footprint"].as_array().unwrap();
+        assert!(!footprint.is_empty());
+        for vertex in footprint {
+            let (x, y) = (vertex["x"].as_f64().unwrap(), vertex["y"].as_f64().unwrap());
+            assert!((bounds.x..=bounds.x + bounds.width).contains(&x));
+            assert!((bounds.y..=bounds.y + bounds.height).contains(&y));
+        }
+    }
+
+    #[test]
+    fn test_append_room_rejects_unknown_neighbor() {
+        let mut layout = single_room_layout();
+        let err = append_room(&mut layout, &RoomConfig::default(), "nope", Direction::Up);
+
+        assert!(err.is_err());
+        assert_eq!(layout.rooms.len(), 1);
+    }
+
+    #[test]
+    fn test_append_room_rejects_inverted_size_range() {
+        let mut layout = single_room_layout();
+        let config = RoomConfig {
+            min_width: 12.0,
+            max_width: 4.0,
+            ..RoomConfig::default()
+        };
+
+        let err = append_room(&mut layout, &config, "room_0", Direction::Right);
+
+        assert!(err.is_err());
+        assert_eq!(layout.rooms.len(), 1);
+    }
+
+    #[test]
+    fn test_append_room_rejects_infinite_size_span() {
+        let mut layout = single_room_layout();
+        let config = RoomConfig {
+            min_width: -1e308,
+            max_width: 1e308,
+            ..RoomConfig::default()
+        };
+
+        let err = append_room(&mut layout, &config, "room_0", Direction::Right);
+
+        assert!(err.is_err());
+        assert_eq!(layout.rooms.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_middle_room_keeps_neighbors_connected() {
+        // room_0 - room_1 (corridor) - room_2, plus a spawn inside the corridor
+        let mut layout = single_room_layout();
+        append_room(
+            &mut layout,
+            &RoomConfig::default(),
+            "room_0",
+            Direction::Right,
+        )
+        .unwrap();
+        append_room(
+            &mut layout,
+            &RoomConfig::default(),
+            "room_1",
+            Direction::Right,
+        )
+        .unwrap();
+        layout.spawn_points.push(crate::models::SpawnPoint {
+            id: "spawn_0".to_string(),
+            spawn_type: "enemy".to_string(),
+            position: RoomGenerator::get_center(&layout.rooms[1]),
+            room_id: "room_1".to_string(),
+        });
+
+        remove_room(&mut layout, "room_1").unwrap();
+
+        assert_eq!(layout.rooms.len(), 2);
+        assert!(layout.spawn_points.is_empty());
+        assert_eq!(layout.connections.len(), 1);
+        assert!(connected(&layout, "room_0", "room_2"));
+        assert!(layout
+            .connections
+            .iter()
+            .all(|c| c.from_room_id != "room_1" && c.to_room_id != "room_1"));
+    }
+
+    #[test]
+    fn test_reskin_changes_cosmetics_only() {
+        let mut layout = single_room_layout();
+        append_room(
+            &mut layout,
+            &RoomConfig::default(),
+            "room_0",
+            Direction::Right,
+        )
+        .unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        RoomGenerator::add_entities(&mut rng, &mut layout.rooms[1], "prop", 4, 4, 0.0);
+        for entity in &mut layout.rooms[1].entities {
+            entity.rotation = Some(0.0);
+        }
+
+        let mut first = layout.clone();
+        let mut second = layout.clone();
+        reskin_layout(&mut first, 1);
+        reskin_layout(&mut second, 2);
+
+        let geometry = |l: &DungeonLayout| {
+            serde_json::to_string(&(
+                l.rooms
+                    .iter()
+                    .map(|r| {
+                        (
+                            &r.bounds,
+                            r.entities.iter().map(|e| &e.position).collect::<Vec<_>>(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+                &l.connections,
+            ))
+            .unwrap()
+        };
+        assert_eq!(geometry(&first), geometry(&layout));
+        assert_eq!(geometry(&second), geometry(&layout));
+
+        assert_ne!(
+            first.rooms[0].metadata["color"],
+            second.rooms[0].metadata["color"]
+        );
+        let rotations = |l: &DungeonLayout| -> Vec<Option<f64>> {
+            l.rooms[1].entities.iter().map(|e| e.rotation).collect()
+        };
+        assert_ne!(rotations(&first), rotations(&second));
+
+        // Same seed, same skin
+        let mut again = layout.clone();
+        reskin_layout(&mut again, 1);
+        assert_eq!(
+            serde_json::to_string(&again).unwrap(),
+            serde_json::to_string(&first).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_dedupe_spawns_merges_near_coincident_spawns() {
+        let spawn = |id: &str, x: f64, room_id: &str| crate::models::SpawnPoint {
+            id: id.to_string(),
+            spawn_type: "enemy".to_string(),
+            position: LayoutPosition { x, y: 5.0 },
+            room_id: room_id.to_string(),
+        };
+        let mut layout = single_room_layout();
+        layout.spawn_points = vec![
+            spawn("spawn_0", 5.0, "room_0"),
+            spawn("spawn_1", 5.2, "room_0"),
+            spawn("spawn_2", 9.0, "room_0"),
+            // Same spot, but counted separately because it's another room
+            spawn("spawn_3", 5.1, "room_1"),
+        ];
+
+        dedupe_spawns(&mut layout, 1.0);
+
+        let ids: Vec<&str> = layout.spawn_points.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["spawn_0", "spawn_2", "spawn_3"]);
+    }
+
+    #[test]
+    fn test_rebuild_connections_mst_connects_every_room() {
+        let mut layout = single_room_layout();
+        for (i, (x, y)) in [(30.0, 0.0), (0.0, 40.0), (60.0, 25.0), (-35.0, -10.0)]
+            .iter()
+            .enumerate()
+        {
+            let mut room = layout.rooms[0].clone();
+            room.id = format!("room_{}", i + 1);
+            room.bounds.x = *x;
+            room.bounds.y = *y;
+            layout.rooms.push(room);
+        }
+
+        rebuild_connections(&mut layout, "mst").unwrap();
+
+        assert_eq!(layout.connections.len(), 4);
+        let distances = LayoutGraph::new(&layout).distances_from(0);
+        assert!(distances.iter().all(|d| d.is_some()));
+        assert!(rebuild_connections(&mut layout, "spiral").is_err());
+    }
+
+    #[test]
+    fn test_rebuild_connections_handles_zero_size_room() {
+        let mut layout = single_room_layout();
+        let mut flat = layout.rooms[0].clone();
+        flat.id = "room_1".to_string();
+        flat.bounds = Rectangle {
+            x: 20.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+        };
+        layout.rooms.push(flat);
+
+        rebuild_connections(&mut layout, "nearest").unwrap();
+
+        assert_eq!(layout.connections.len(), 1);
+        let door = &layout.connections[0].to_door;
+        assert_eq!((door.x, door.y), (20.0, 0.0));
+    }
+}
@@ -0,0 +1,750 @@
+//! Layout exporters for external tools and engines
+
+use super::analysis::layout_bounds;
+use super::RoomGenerator;
+use crate::models::{
+    DungeonLayout, ExportTarget, LayoutPosition, MinimapData, MinimapRoom, MinimapSegment,
+    Rectangle,
+};
+use std::fmt::Write;
+
+/// Pixel size of one tile cell in exported maps
+const TILE_SIZE: u32 = 16;
+/// Tileset global ids: 0 is empty in Tiled, so floor and wall start at 1
+const FLOOR_GID: u32 = 1;
+const WALL_GID: u32 = 2;
+/// Largest tile grid an export will allocate (4096 x 4096 cells)
+const MAX_TILE_CELLS: usize = 4096 * 4096;
+
+/// Render a layout as a Tiled `.tmx` map.
+///
+/// One world unit maps to one tile. Rooms become a "rooms" tile layer (using
+/// their `tiles` grid when present, otherwise a walled rectangle rasterized on
+/// the fly), and spawn points and entities become objects in an "entities"
+/// object layer. Layouts whose bounding box exceeds `MAX_TILE_CELLS` tiles are
+/// rejected.
+pub fn to_tiled_tmx(layout: &DungeonLayout) -> Result<String, String> {
+    let grid = rasterize(layout)?;
+    let (origin_x, origin_y) = grid.origin;
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" \
+         width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" infinite=\"0\" \
+         nextlayerid=\"3\" nextobjectid=\"{}\">\n",
+        grid.width,
+        grid.height,
+        TILE_SIZE,
+        TILE_SIZE,
+        layout.spawn_points.len()
+            + layout.rooms.iter().map(|r| r.entities.len()).sum::<usize>()
+            + 1
+    ));
+    xml.push_str(&format!(
+        " <tileset firstgid=\"1\" name=\"dungeon\" tilewidth=\"{0}\" tileheight=\"{0}\" \
+         tilecount=\"2\" columns=\"2\"/>\n",
+        TILE_SIZE
+    ));
+
+    xml.push_str(&format!(
+        " <layer id=\"1\" name=\"rooms\" width=\"{}\" height=\"{}\">\n  <data encoding=\"csv\">\n",
+        grid.width, grid.height
+    ));
+    let rows: Vec<String> = grid
+        .cells
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|gid| gid.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect();
+    xml.push_str(&rows.join(",\n"));
+    xml.push_str("\n  </data>\n </layer>\n");
+
+    xml.push_str(" <objectgroup id=\"2\" name=\"entities\">\n");
+    let mut object_id = 1;
+    let to_pixels = |x: f64, y: f64| {
+        (
+            (x - origin_x) * TILE_SIZE as f64,
+            (y - origin_y) * TILE_SIZE as f64,
+        )
+    };
+    for spawn in &layout.spawn_points {
+        let (x, y) = to_pixels(spawn.position.x, spawn.position.y);
+        xml.push_str(&format!(
+            "  <object id=\"{}\" name=\"{}\" type=\"{}\" x=\"{}\" y=\"{}\"><point/></object>\n",
+            object_id,
+            escape_xml(&spawn.id),
+            escape_xml(&spawn.spawn_type),
+            x,
+            y
+        ));
+        object_id += 1;
+    }
+    for entity in layout.rooms.iter().flat_map(|r| &r.entities) {
+        let (x, y) = to_pixels(entity.position.x, entity.position.y);
+        xml.push_str(&format!(
+            "  <object id=\"{}\" name=\"{}\" type=\"{}\" x=\"{}\" y=\"{}\"><point/></object>\n",
+            object_id,
+            escape_xml(&entity.id),
+            escape_xml(&entity.entity_type),
+            x,
+            y
+        ));
+        object_id += 1;
+    }
+    xml.push_str(" </objectgroup>\n</map>\n");
+
+    Ok(xml)
+}
+
+/// A layout rasterized onto a single tile grid of Tiled global ids
+struct TileGrid {
+    origin: (f64, f64),
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<u32>>,
+}
+
+fn rasterize(layout: &DungeonLayout) -> Result<TileGrid, String> {
+    if layout.rooms.is_empty() {
+        return Ok(TileGrid {
+            origin: (0.0, 0.0),
+            width: 0,
+            height: 0,
+            cells: vec![],
+        });
+    }
+
+    let min_x = layout
+        .rooms
+        .iter()
+        .map(|r| r.bounds.x)
+        .fold(f64::INFINITY, f64::min)
+        .floor();
+    let min_y = layout
+        .rooms
+        .iter()
+        .map(|r| r.bounds.y)
+        .fold(f64::INFINITY, f64::min)
+        .floor();
+    let max_x = layout
+        .rooms
+        .iter()
+        .map(|r| r.bounds.x + r.bounds.width)
+        .fold(f64::NEG_INFINITY, f64::max)
+        .ceil();
+    let max_y = layout
+        .rooms
+        .iter()
+        .map(|r| r.bounds.y + r.bounds.height)
+        .fold(f64::NEG_INFINITY, f64::max)
+        .ceil();
+
+    let width = (max_x - min_x) as usize;
+    let height = (max_y - min_y) as usize;
+    if width
+        .checked_mul(height)
+        .is_none_or(|cells| cells > MAX_TILE_CELLS)
+    {
+        return Err(format!(
+            "Layout is too large to export as tiles ({} x {})",
+            max_x - min_x,
+            max_y - min_y
+        ));
+    }
+    let mut cells = vec![vec![0; width]; height];
+
+    for room in &layout.rooms {
+        let left = (room.bounds.x - min_x).floor() as usize;
+        let top = (room.bounds.y - min_y).floor() as usize;
+
+        match &room.tiles {
+            Some(tiles) => {
+                for (row, line) in tiles.iter().enumerate() {
+                    for (col, tile) in line.iter().enumerate() {
+                        if let Some(cell) =
+                            cells.get_mut(top + row).and_then(|r| r.get_mut(left + col))
+                        {
+                            *cell = if *tile > 0 { FLOOR_GID } else { WALL_GID };
+                        }
+                    }
+                }
+            }
+            None => {
+                let right =
+                    ((room.bounds.x + room.bounds.width - min_x).ceil() as usize).min(width);
+                let bottom =
+                    ((room.bounds.y + room.bounds.height - min_y).ceil() as usize).min(height);
+                for (row, line) in cells.iter_mut().enumerate().take(bottom).skip(top) {
+                    for (col, cell) in line.iter_mut().enumerate().take(right).skip(left) {
+                        let on_border =
+                            row == top || row + 1 == bottom || col == left || col + 1 == right;
+                        *cell = if on_border { WALL_GID } else { FLOOR_GID };
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(TileGrid {
+        origin: (min_x, min_y),
+        width,
+        height,
+        cells,
+    })
+}
+
+/// Minimap colors per room type, matching the editor's preview canvas
+fn room_type_color(room_type: &str) -> &'static str {
+    match room_type {
+        "start" => "#22c55e",
+        "boss" => "#ef4444",
+        "treasure" => "#f59e0b",
+        "shop" => "#06b6d4",
+        "secret" => "#8b5cf6",
+        _ => "#3b82f6",
+    }
+}
+
+/// Scale a layout uniformly so its longer side spans `target_size`.
+///
+/// Coordinates are shifted so the layout's bounding box starts at the origin.
+/// Connections become straight door-to-door segments.
+pub fn to_minimap(layout: &DungeonLayout, target_size: f64) -> MinimapData {
+    let Some(bounds) = layout_bounds(layout) else {
+        return MinimapData {
+            width: 0.0,
+            height: 0.0,
+            rooms: vec![],
+            connections: vec![],
+        };
+    };
+    let longest = bounds.width.max(bounds.height);
+    let scale = if longest > 0.0 {
+        target_size.max(0.0) / longest
+    } else {
+        0.0
+    };
+    let point = |p: &LayoutPosition| LayoutPosition {
+        x: (p.x - bounds.x) * scale,
+        y: (p.y - bounds.y) * scale,
+    };
+
+    MinimapData {
+        width: bounds.width * scale,
+        height: bounds.height * scale,
+        rooms: layout
+            .rooms
+            .iter()
+            .map(|room| MinimapRoom {
+                id: room.id.clone(),
+                room_type: room.room_type.clone(),
+                bounds: Rectangle {
+                    x: (room.bounds.x - bounds.x) * scale,
+                    y: (room.bounds.y - bounds.y) * scale,
+                    width: room.bounds.width * scale,
+                    height: room.bounds.height * scale,
+                },
+                color: room_type_color(&room.room_type).to_string(),
+            })
+            .collect(),
+        connections: layout
+            .connections
+            .iter()
+            .map(|c| MinimapSegment {
+                from: point(&c.from_door),
+                to: point(&c.to_door),
+            })
+            .collect(),
+    }
+}
+
+/// Room graph in node-link form (`{ nodes, links }`) for D3 and similar libraries.
+///
+/// Nodes are rooms positioned at their centers; links are connections by room id.
+pub fn to_node_link(layout: &DungeonLayout) -> serde_json::Value {
+    let nodes: Vec<serde_json::Value> = layout
+        .rooms
+        .iter()
+        .map(|room| {
+            let center = RoomGenerator::get_center(room);
+            serde_json::json!({
+                "id": room.id,
+                "type": room.room_type,
+                "x": center.x,
+                "y": center.y,
+            })
+        })
+        .collect();
+    let links: Vec<serde_json::Value> = layout
+        .connections
+        .iter()
+        .map(|c| serde_json::json!({ "source": c.from_room_id, "target": c.to_room_id }))
+        .collect();
+
+    serde_json::json!({ "nodes": nodes, "links": links })
+}
+
+/// Serialize a layout for `target`.
+///
+/// JSON is the layout's serde form. The code targets declare types for rooms,
+/// connections and spawn points and then a single constant holding the layout;
+/// entities, tiles and metadata are left out.
+pub fn export_layout(layout: &DungeonLayout, target: &ExportTarget) -> Result<String, String> {
+    match target {
+        ExportTarget::Json => serde_json::to_string_pretty(layout)
+            .map_err(|e| format!("Failed to serialize layout: {}", e)),
+        ExportTarget::Typescript => Ok(to_typescript(layout)),
+        ExportTarget::Rust => Ok(to_rust(layout)),
+        ExportTarget::Csharp => Ok(to_csharp(layout)),
+        ExportTarget::Gdscript => Ok(to_gdscript(layout)),
+    }
+}
+
+/// Float literal valid in every target language (always has a decimal point or exponent)
+fn num(value: f64) -> String {
+    format!("{:?}", value)
+}
+
+/// Double-quoted string literal; JSON escapes are accepted by TypeScript, C# and GDScript
+fn quote(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+fn to_typescript(layout: &DungeonLayout) -> String {
+    let point = |p: &LayoutPosition| format!("{{ x: {}, y: {} }}", num(p.x), num(p.y));
+    let mut out = String::from(
+        "export interface Point {\n  x: number;\n  y: number;\n}\n\n\
+         export interface Rect {\n  x: number;\n  y: number;\n  width: number;\n  height: number;\n}\n\n\
+         export interface Room {\n  id: string;\n  type: string;\n  bounds: Rect;\n}\n\n\
+         export interface Connection {\n  from: string;\n  to: string;\n  fromDoor: Point;\n  toDoor: Point;\n}\n\n\
+         export interface SpawnPoint {\n  id: string;\n  type: string;\n  roomId: string;\n  position: Point;\n}\n\n\
+         export interface DungeonLayout {\n  rooms: Room[];\n  connections: Connection[];\n  \
+         spawnPoints: SpawnPoint[];\n  playerStart: Point;\n  exits: Point[];\n}\n\n\
+         export const dungeon: DungeonLayout = {\n  rooms: [\n",
+    );
+    for room in &layout.rooms {
+        let b = &room.bounds;
+        let _ = writeln!(
+            out,
+            "    {{ id: {}, type: {}, bounds: {{ x: {}, y: {}, width: {}, height: {} }} }},",
+            quote(&room.id),
+            quote(&room.room_type),
+            num(b.x),
+            num(b.y),
+            num(b.width),
+            num(b.height)
+        );
+    }
+    out.push_str("  ],\n  connections: [\n");
+    for conn in &layout.connections {
+        let _ = writeln!(
+            out,
+            "    {{ from: {}, to: {}, fromDoor: {}, toDoor: {} }},",
+            quote(&conn.from_room_id),
+            quote(&conn.to_room_id),
+            point(&conn.from_door),
+            point(&conn.to_door)
+        );
+    }
+    out.push_str("  ],\n  spawnPoints: [\n");
+    for spawn in &layout.spawn_points {
+        let _ = writeln!(
+            out,
+            "    {{ id: {}, type: {}, roomId: {}, position: {} }},",
+            quote(&spawn.id),
+            quote(&spawn.spawn_type),
+            quote(&spawn.room_id),
+            point(&spawn.position)
+        );
+    }
+    let exits: Vec<String> = layout.exits.iter().map(point).collect();
+    let _ = write!(
+        out,
+        "  ],\n  playerStart: {},\n  exits: [{}],\n}};\n",
+        point(&layout.player_start),
+        exits.join(", ")
+    );
+    out
+}
+
+fn to_rust(layout: &DungeonLayout) -> String {
+    let point = |p: &LayoutPosition| format!("Point {{ x: {}, y: {} }}", num(p.x), num(p.y));
+    let mut out = String::from(
+        "#[derive(Debug, Clone, Copy)]\npub struct Point {\n    pub x: f64,\n    pub y: f64,\n}\n\n\
+         #[derive(Debug, Clone, Copy)]\npub struct Rect {\n    pub x: f64,\n    pub y: f64,\n    \
+         pub width: f64,\n    pub height: f64,\n}\n\n\
+         #[derive(Debug)]\npub struct Room {\n    pub id: &'static str,\n    \
+         pub room_type: &'static str,\n    pub bounds: Rect,\n}\n\n\
+         #[derive(Debug)]\npub struct Connection {\n    pub from: &'static str,\n    \
+         pub to: &'static str,\n    pub from_door: Point,\n    pub to_door: Point,\n}\n\n\
+         #[derive(Debug)]\npub struct SpawnPoint {\n    pub id: &'static str,\n    \
+         pub spawn_type: &'static str,\n    pub room_id: &'static str,\n    pub position: Point,\n}\n\n\
+         #[derive(Debug)]\npub struct DungeonLayout {\n    pub rooms: &'static [Room],\n    \
+         pub connections: &'static [Connection],\n    pub spawn_points: &'static [SpawnPoint],\n    \
+         pub player_start: Point,\n    pub exits: &'static [Point],\n}\n\n\
+         pub const DUNGEON: DungeonLayout = DungeonLayout {\n    rooms: &[\n",
+    );
+    for room in &layout.rooms {
+        let b = &room.bounds;
+        let _ = writeln!(
+            out,
+            "        Room {{ id: {:?}, room_type: {:?}, bounds: Rect {{ x: {}, y: {}, width: {}, height: {} }} }},",
+            room.id,
+            room.room_type,
+            num(b.x),
+            num(b.y),
+            num(b.width),
+            num(b.height)
+        );
+    }
+    out.push_str("    ],\n    connections: &[\n");
+    for conn in &layout.connections {
+        let _ = writeln!(
+            out,
+            "        Connection {{ from: {:?}, to: {:?}, from_door: {}, to_door: {} }},",
+            conn.from_room_id,
+            conn.to_room_id,
+            point(&conn.from_door),
+            point(&conn.to_door)
+        );
+    }
+    out.push_str("    ],\n    spawn_points: &[\n");
+    for spawn in &layout.spawn_points {
+        let _ = writeln!(
+            out,
+            "        SpawnPoint {{ id: {:?}, spawn_type: {:?}, room_id: {:?}, position: {} }},",
+            spawn.id,
+            spawn.spawn_type,
+            spawn.room_id,
+            point(&spawn.position)
+        );
+    }
+    let exits: Vec<String> = layout.exits.iter().map(point).collect();
+    let _ = write!(
+        out,
+        "    ],\n    player_start: {},\n    exits: &[{}],\n}};\n",
+        point(&layout.player_start),
+        exits.join(", ")
+    );
+    out
+}
+
+fn to_csharp(layout: &DungeonLayout) -> String {
+    let point = |p: &LayoutPosition| format!("new Point({}, {})", num(p.x), num(p.y));
+    let mut out = String::from(
+        "public record Point(double X, double Y);\n\
+         public record Rect(double X, double Y, double Width, double Height);\n\
+         public record Room(string Id, string Type, Rect Bounds);\n\
+         public record Connection(string From, string To, Point FromDoor, Point ToDoor);\n\
+         public record SpawnPoint(string Id, string Type, string RoomId, Point Position);\n\
+         public record DungeonLayout(Room[] Rooms, Connection[] Connections, \
+         SpawnPoint[] SpawnPoints, Point PlayerStart, Point[] Exits);\n\n\
+         public static class GeneratedDungeon\n{\n    \
+         public static readonly DungeonLayout Layout = new DungeonLayout(\n        new Room[]\n        {\n",
+    );
+    for room in &layout.rooms {
+        let b = &room.bounds;
+        let _ = writeln!(
+            out,
+            "            new Room({}, {}, new Rect({}, {}, {}, {})),",
+            quote(&room.id),
+            quote(&room.room_type),
+            num(b.x),
+            num(b.y),
+            num(b.width),
+            num(b.height)
+        );
+    }
+    out.push_str("        },\n        new Connection[]\n        {\n");
+    for conn in &layout.connections {
+        let _ = writeln!(
+            out,
+            "            new Connection({}, {}, {}, {}),",
+            quote(&conn.from_room_id),
+            quote(&conn.to_room_id),
+            point(&conn.from_door),
+            point(&conn.to_door)
+        );
+    }
+    out.push_str("        },\n        new SpawnPoint[]\n        {\n");
+    for spawn in &layout.spawn_points {
+        let _ = writeln!(
+            out,
+            "            new SpawnPoint({}, {}, {}, {}),",
+            quote(&spawn.id),
+            quote(&spawn.spawn_type),
+            quote(&spawn.room_id),
+            point(&spawn.position)
+        );
+    }
+    let exits: Vec<String> = layout.exits.iter().map(point).collect();
+    let _ = write!(
+        out,
+        "        }},\n        {},\n        new Point[] {{ {} }}\n    );\n}}\n",
+        point(&layout.player_start),
+        exits.join(", ")
+    );
+    out
+}
+
+fn to_gdscript(layout: &DungeonLayout) -> String {
+    let point = |p: &LayoutPosition| format!("Vector2({}, {})", num(p.x), num(p.y));
+    let mut out = String::from("const DUNGEON := {\n\t\"rooms\": [\n");
+    for room in &layout.rooms {
+        let b = &room.bounds;
+        let _ = writeln!(
+            out,
+            "\t\t{{\"id\": {}, \"type\": {}, \"bounds\": Rect2({}, {}, {}, {})}},",
+            quote(&room.id),
+            quote(&room.room_type),
+            num(b.x),
+            num(b.y),
+            num(b.width),
+            num(b.height)
+        );
+    }
+    out.push_str("\t],\n\t\"connections\": [\n");
+    for conn in &layout.connections {
+        let _ = writeln!(
+            out,
+            "\t\t{{\"from\": {}, \"to\": {}, \"from_door\": {}, \"to_door\": {}}},",
+            quote(&conn.from_room_id),
+            quote(&conn.to_room_id),
+            point(&conn.from_door),
+            point(&conn.to_door)
+        );
+    }
+    out.push_str("\t],\n\t\"spawn_points\": [\n");
+    for spawn in &layout.spawn_points {
+        let _ = writeln!(
+            out,
+            "\t\t{{\"id\": {}, \"type\": {}, \"room_id\": {}, \"position\": {}}},",
+            quote(&spawn.id),
+            quote(&spawn.spawn_type),
+            quote(&spawn.room_id),
+            point(&spawn.position)
+        );
+    }
+    let exits: Vec<String> = layout.exits.iter().map(point).collect();
+    let _ = write!(
+        out,
+        "\t],\n\t\"player_start\": {},\n\t\"exits\": [{}],\n}}\n",
+        point(&layout.player_start),
+        exits.join(", ")
+    );
+    out
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        GeneratedRoom, LayoutPosition, PlacedEntity, Rectangle, RoomConnection, SpawnPoint,
+    };
+    use std::collections::HashMap;
+
+    fn sample_layout() -> DungeonLayout {
+        DungeonLayout {
+            rooms: vec![GeneratedRoom {
+                id: "room_0".to_string(),
+                room_type: "start".to_string(),
+                bounds: Rectangle {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 6.0,
+                    height: 5.0,
+                },
+                tiles: None,
+                entities: vec![PlacedEntity {
+                    id: "chest".to_string(),
+                    entity_type: "loot".to_string(),
+                    position: LayoutPosition { x: 2.0, y: 2.0 },
+                    rotation: None,
+                    metadata: HashMap::new(),
+                }],
+                metadata: HashMap::new(),
+            }],
+            connections: vec![],
+            spawn_points: vec![SpawnPoint {
+                id: "spawn_0".to_string(),
+                spawn_type: "enemy".to_string(),
+                position: LayoutPosition { x: 3.0, y: 3.0 },
+                room_id: "room_0".to_string(),
+            }],
+            player_start: LayoutPosition { x: 1.0, y: 1.0 },
+            exits: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Minimal well-formedness check: every opened tag is closed in order
+    fn assert_well_formed(xml: &str) {
+        let mut stack: Vec<String> = vec![];
+        let mut rest = xml;
+        while let Some(open) = rest.find('<') {
+            let close = rest[open..].find('>').expect("unterminated tag") + open;
+            let tag = &rest[open + 1..close];
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(stack.pop().as_deref(), Some(name.trim()));
+            } else if !tag.starts_with('?') && !tag.ends_with('/') {
+                stack.push(tag.split_whitespace().next().unwrap().to_string());
+            }
+            rest = &rest[close + 1..];
+        }
+        assert!(stack.is_empty(), "unclosed tags: {:?}", stack);
+    }
+
+    #[test]
+    fn test_tiled_export_is_well_formed_with_layers() {
+        let xml = to_tiled_tmx(&sample_layout()).unwrap();
+
+        assert_well_formed(&xml);
+        assert!(xml.contains("<layer id=\"1\" name=\"rooms\" width=\"6\" height=\"5\">"));
+        assert!(xml.contains("<objectgroup id=\"2\" name=\"entities\">"));
+        assert!(xml.contains("type=\"enemy\""));
+        assert!(xml.contains("type=\"loot\""));
+    }
+
+    #[test]
+    fn test_rasterize_walls_and_floor() {
+        let grid = rasterize(&sample_layout()).unwrap();
+
+        assert_eq!(grid.cells[0], vec![WALL_GID; 6]);
+        assert_eq!(grid.cells[2][0], WALL_GID);
+        assert_eq!(grid.cells[2][1], FLOOR_GID);
+    }
+
+    #[test]
+    fn test_tiled_export_rejects_huge_layouts() {
+        let mut layout = sample_layout();
+        let mut far = layout.rooms[0].clone();
+        far.id = "room_far".to_string();
+        far.bounds.x = 1e6;
+        far.bounds.y = 1e6;
+        layout.rooms.push(far);
+
+        assert!(to_tiled_tmx(&layout).is_err());
+    }
+
+    #[test]
+    fn test_minimap_fits_target_size() {
+        let mut layout = sample_layout();
+        layout.rooms.push(GeneratedRoom {
+            id: "room_1".to_string(),
+            room_type: "boss".to_string(),
+            bounds: Rectangle {
+                x: 40.0,
+                y: -10.0,
+                width: 20.0,
+                height: 30.0,
+            },
+            tiles: None,
+            entities: vec![],
+            metadata: HashMap::new(),
+        });
+        layout.connections.push(crate::models::RoomConnection {
+            from_room_id: "room_0".to_string(),
+            to_room_id: "room_1".to_string(),
+            from_door: LayoutPosition { x: 6.0, y: 2.5 },
+            to_door: LayoutPosition { x: 40.0, y: 2.5 },
+        });
+
+        let minimap = to_minimap(&layout, 100.0);
+
+        assert_eq!(minimap.rooms.len(), layout.rooms.len());
+        assert_eq!(minimap.connections.len(), 1);
+        assert!((minimap.width - 100.0).abs() < 1e-9);
+        let within = |v: f64| (-1e-9..=100.0 + 1e-9).contains(&v);
+        for room in &minimap.rooms {
+            let b = &room.bounds;
+            assert!(within(b.x) && within(b.y));
+            assert!(within(b.x + b.width) && within(b.y + b.height));
+        }
+        for segment in &minimap.connections {
+            assert!(within(segment.from.x) && within(segment.from.y));
+            assert!(within(segment.to.x) && within(segment.to.y));
+        }
+        assert_eq!(minimap.rooms[1].color, "#ef4444");
+    }
+
+    #[test]
+    fn test_typescript_export_declares_interface_and_rooms() {
+        let mut layout = sample_layout();
+        layout.rooms[0].room_type = "say \"hi\"".to_string();
+
+        let ts = export_layout(&layout, &ExportTarget::Typescript).unwrap();
+
+        assert!(ts.contains("export interface DungeonLayout {"));
+        assert!(ts.contains("export const dungeon: DungeonLayout = {"));
+        assert!(ts.contains(
+            r#"{ id: "room_0", type: "say \"hi\"", bounds: { x: 0.0, y: 0.0, width: 6.0, height: 5.0 } },"#
+        ));
+        assert!(ts.contains(
+            r#"{ id: "spawn_0", type: "enemy", roomId: "room_0", position: { x: 3.0, y: 3.0 } },"#
+        ));
+        assert_eq!(ts.matches('{').count(), ts.matches('}').count());
+    }
+
+    #[test]
+    fn test_every_target_exports_room_ids() {
+        let layout = sample_layout();
+        for target in [
+            ExportTarget::Json,
+            ExportTarget::Typescript,
+            ExportTarget::Rust,
+            ExportTarget::Csharp,
+            ExportTarget::Gdscript,
+        ] {
+            let code = export_layout(&layout, &target).unwrap();
+            assert!(code.contains("\"room_0\""), "{:?}", target);
+            assert!(code.contains("\"spawn_0\""), "{:?}", target);
+        }
+
+        let json = export_layout(&layout, &ExportTarget::Json).unwrap();
+        let parsed: DungeonLayout = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.rooms.len(), 1);
+    }
+
+    #[test]
+    fn test_node_link_mirrors_rooms_and_connections() {
+        let mut layout = sample_layout();
+        for (i, x) in [(1, 10.0), (2, 20.0)] {
+            let mut room = layout.rooms[0].clone();
+            room.id = format!("room_{}", i);
+            room.bounds.x = x;
+            layout.rooms.push(room);
+        }
+        for (from, to) in [("room_0", "room_1"), ("room_1", "room_2")] {
+            layout.connections.push(RoomConnection {
+                from_room_id: from.to_string(),
+                to_room_id: to.to_string(),
+                from_door: LayoutPosition { x: 0.0, y: 0.0 },
+                to_door: LayoutPosition { x: 0.0, y: 0.0 },
+            });
+        }
+
+        let graph = to_node_link(&layout);
+        let nodes = graph["nodes"].as_array().unwrap();
+        let links = graph["links"].as_array().unwrap();
+
+        assert_eq!(nodes.len(), layout.rooms.len());
+        assert_eq!(links.len(), layout.connections.len());
+        assert_eq!(nodes[1]["x"], 13.0);
+        assert_eq!(links[1]["source"], "room_1");
+        assert_eq!(links[1]["target"], "room_2");
+    }
+}
@@ -6,25 +6,121 @@
 //! 3. Executing each node type to build the dungeon
 //! 4. Outputting at the Output node
 
-use super::room_generator::{Direction, RoomConfig, RoomGenerator, RoomShape};
+use super::layout_graph;
+use super::postprocess;
+use super::room_generator::{
+    rectangles_overlap, Direction, PropPlacement, RoomConfig, RoomGenerator, RoomShape,
+    SizeDistribution,
+};
+use super::sampling::{standard_normal, uniform};
+use super::weights::{pick_weighted, scale_weights};
 use crate::models::{
-    generator::{Edge, Generator, GraphNode, NodeType},
-    result::{DungeonLayout, GeneratedRoom, LayoutPosition, RoomConnection, SpawnPoint},
+    generator::{Edge, Generator, GraphNode, NodeGraph, NodeType},
+    result::{
+        DungeonLayout, GeneratedRoom, LayoutPosition, LootItem, LootResult, PlacedEntity,
+        Rectangle, RoomConnection, SpawnPoint,
+    },
 };
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Errors that abort graph execution
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionError {
+    /// The graph contains no nodes at all (e.g. a corrupt project)
+    EmptyGraph,
+    /// The graph has nodes but none of them is a Start node
+    MissingStart,
+    /// Two nodes share an id, so edges can't be routed unambiguously
+    DuplicateNodeId(String),
+    NodeNotFound(String),
+    /// Node ids of a cycle no Loop or RandomSelect can end, first id repeated last
+    Cycle(Vec<String>),
+    /// Backstop for runaway Loop/RandomSelect recursion
+    ExecutionLimitExceeded,
+    /// The wall-clock budget (`timeoutMs`) ran out; carries progress made so far
+    Timeout {
+        elapsed_ms: u64,
+        node_executions: u32,
+        rooms: usize,
+    },
+    /// No free spot inside `maxBounds` for the next room
+    OutOfBounds {
+        width: f64,
+        height: f64,
+        rooms: usize,
+    },
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionError::EmptyGraph => write!(f, "Graph is empty (no nodes)"),
+            ExecutionError::MissingStart => write!(f, "No Start node found in graph"),
+            ExecutionError::DuplicateNodeId(id) => {
+                write!(f, "Duplicate node id '{}' in graph", id)
+            }
+            ExecutionError::NodeNotFound(id) => write!(f, "Node {} not found", id),
+            ExecutionError::Cycle(nodes) => write!(
+                f,
+                "Graph cycle {} never ends; route it through a Loop node",
+                nodes.join(" -> ")
+            ),
+            ExecutionError::ExecutionLimitExceeded => write!(
+                f,
+                "Executed more than {} nodes; a Loop or RandomSelect cycle may be repeating without end",
+                MAX_NODE_EXECUTIONS
+            ),
+            ExecutionError::Timeout {
+                elapsed_ms,
+                node_executions,
+                rooms,
+            } => write!(
+                f,
+                "Generation timed out after {}ms ({} node executions, {} rooms placed)",
+                elapsed_ms, node_executions, rooms
+            ),
+            ExecutionError::OutOfBounds {
+                width,
+                height,
+                rooms,
+            } => write!(
+                f,
+                "Could not fit room within maxBounds {}x{} ({} rooms placed)",
+                width, height, rooms
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionError {}
 
 /// Execution context that tracks state during graph traversal
-#[derive(Debug)]
+///
+/// Serializable so the editor can hand a context to `preview_node` and show
+/// the result; missing fields take their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExecutionContext {
     pub rooms: Vec<GeneratedRoom>,
     pub connections: Vec<RoomConnection>,
+    #[serde(rename = "spawnPoints")]
     pub spawn_points: Vec<SpawnPoint>,
+    #[serde(rename = "currentPosition")]
     pub current_position: LayoutPosition,
+    #[serde(rename = "currentDirection")]
     pub current_direction: Direction,
+    /// Rooms placed along the current traversal path (branches track their own)
+    pub depth: u32,
+    #[serde(rename = "nodeExecutions")]
     pub node_executions: u32,
     pub variables: HashMap<String, serde_json::Value>,
+    #[serde(rename = "layoutMetadata")]
+    pub layout_metadata: HashMap<String, serde_json::Value>,
 }
 
 impl Default for ExecutionContext {
@@ -35,46 +131,187 @@ impl Default for ExecutionContext {
             spawn_points: Vec::new(),
             current_position: LayoutPosition { x: 0.0, y: 0.0 },
             current_direction: Direction::Right,
+            depth: 0,
             node_executions: 0,
             variables: HashMap::new(),
+            layout_metadata: HashMap::new(),
+        }
+    }
+}
+
+/// Wall-clock budget for one execution when `timeoutMs` isn't given
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// Side length of the placeholder room emitted by `emitDefaultRoom`
+const DEFAULT_ROOM_SIZE: f64 = 10.0;
+
+/// Empty space kept between rooms unless `minRoomGap` overrides it
+const DEFAULT_MIN_ROOM_GAP: f64 = 1.0;
+
+/// Nudges tried before a colliding room is left where it is
+const MAX_COLLISION_NUDGES: usize = 64;
+
+/// Overlap below this is treated as rooms touching rather than colliding
+const COLLISION_EPSILON: f64 = 1e-9;
+
+/// How a new room that collides with a placed one is handled (`overlapStrategy`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OverlapStrategy {
+    /// Push the room past whatever it hits, up to `MAX_COLLISION_NUDGES` times
+    Nudge,
+    /// Leave the room out of the layout
+    Skip,
+    /// Place the room where it was generated, overlap and all
+    Allow,
+}
+
+impl OverlapStrategy {
+    fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "skip" => OverlapStrategy::Skip,
+            "allow" => OverlapStrategy::Allow,
+            _ => OverlapStrategy::Nudge,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            OverlapStrategy::Nudge => "nudge",
+            OverlapStrategy::Skip => "skip",
+            OverlapStrategy::Allow => "allow",
         }
     }
 }
 
+/// Sideways spacing between successive Branch arms unless the node sets `offset`
+const DEFAULT_BRANCH_OFFSET: f64 = 15.0;
+
+/// Context variables holding the innermost loop's iteration (from 0) and iteration total
+const LOOP_INDEX: &str = "loopIndex";
+const LOOP_COUNT: &str = "loopCount";
+
+/// Node executions allowed per run before giving up on a runaway cycle
+const MAX_NODE_EXECUTIONS: u32 = 1000;
+
 pub struct GraphExecutor {
+    seed: u64,
+    /// Stream of the node currently executing; the master stream outside nodes
     rng: ChaCha8Rng,
+    /// Times each node has started executing this run, selecting its sub-stream
+    node_visits: HashMap<String, u64>,
+    /// Words drawn by node sub-streams that have finished
+    node_draws: u64,
     parameters: HashMap<String, serde_json::Value>,
+    started: Instant,
+    timeout: Duration,
+    /// Region `(width, height)` from the origin that every room must stay inside
+    max_bounds: Option<(f64, f64)>,
+    /// Minimum distance kept between a new room and every placed room
+    min_room_gap: f64,
+    overlap_strategy: OverlapStrategy,
+    /// Nodes executed by the last `execute`/`execute_loot`, including failed runs
+    executions: u32,
+    /// Per-type breakdown of `executions`
+    executions_by_type: HashMap<NodeType, u64>,
+    /// Nodes on the current traversal path, flagged when they may legitimately repeat
+    path: Vec<(String, bool)>,
+    /// Edges a RandomSelect or Condition declined in the innermost loop iteration
+    /// (or the run outside loops); Merge stops waiting for paths behind them
+    skipped_edges: HashSet<String>,
 }
 
 impl GraphExecutor {
     pub fn new(seed: u64, parameters: HashMap<String, serde_json::Value>) -> Self {
+        let timeout_ms = parameters
+            .get("timeoutMs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_TIMEOUT_MS);
+        let max_bounds = parameters.get("maxBounds").and_then(|v| {
+            let width = v.get("width").and_then(|w| w.as_f64())?;
+            let height = v.get("height").and_then(|h| h.as_f64())?;
+            Some((width, height))
+        });
+        let min_room_gap = parameters
+            .get("minRoomGap")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(DEFAULT_MIN_ROOM_GAP)
+            .max(0.0);
+        let overlap_strategy = parameters
+            .get("overlapStrategy")
+            .and_then(|v| v.as_str())
+            .map(OverlapStrategy::from_name)
+            .unwrap_or(OverlapStrategy::Nudge);
         Self {
+            seed,
             rng: ChaCha8Rng::seed_from_u64(seed),
+            node_visits: HashMap::new(),
+            node_draws: 0,
             parameters,
+            started: Instant::now(),
+            timeout: Duration::from_millis(timeout_ms),
+            max_bounds,
+            min_room_gap,
+            overlap_strategy,
+            executions: 0,
+            executions_by_type: HashMap::new(),
+            path: vec![],
+            skipped_edges: HashSet::new(),
         }
     }
 
     /// Get the number of node executions (call after execute)
     pub fn node_executions(&self) -> u32 {
-        // This is a rough estimate based on parameters
-        // In a full implementation, we'd track this in the executor
-        10
+        self.executions
+    }
+
+    /// Node executions of the last run, split by node type
+    pub fn executions_by_type(&self) -> &HashMap<NodeType, u64> {
+        &self.executions_by_type
+    }
+
+    /// 32-bit words drawn so far, across the master stream and every node sub-stream
+    pub fn rng_draws(&self) -> u64 {
+        self.rng.get_word_pos() as u64 + self.node_draws
+    }
+
+    /// Fresh generator for a node, seeded from the master seed and the node id.
+    ///
+    /// Nodes draw from their own streams, so editing or inserting one node
+    /// leaves every other node's random values unchanged.
+    pub fn rng_for_node(&self, node_id: &str) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed ^ fnv1a(node_id.as_bytes()))
     }
 
     /// Execute a generator graph and produce a dungeon layout
-    pub fn execute(&mut self, generator: &Generator) -> Result<DungeonLayout, String> {
+    pub fn execute(&mut self, generator: &Generator) -> Result<DungeonLayout, ExecutionError> {
+        self.started = Instant::now();
+        self.executions_by_type.clear();
+        self.path.clear();
+        self.node_visits.clear();
+        self.skipped_edges.clear();
         let graph = &generator.graph;
         let mut ctx = ExecutionContext::default();
 
-        // Find the start node
-        let start_node = graph
-            .nodes
-            .iter()
-            .find(|n| matches!(n.node_type, NodeType::Start))
-            .ok_or("No Start node found in graph")?;
+        let start_node = Self::find_start_node(graph)?;
 
-        // Execute from start node
-        self.execute_node(&start_node.id, graph, &mut ctx)?;
+        // Execute from start node, keeping the count even if execution fails
+        let outcome = self.execute_node(&start_node.id, graph, &mut ctx);
+        self.executions = ctx.node_executions;
+        outcome?;
+
+        // Give renderers a room to show when a valid graph places none
+        if ctx.rooms.is_empty() && self.emit_default_room() {
+            ctx.rooms.push(Self::default_room());
+        }
+
+        if self
+            .parameters
+            .get("ensureConnected")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            connect_minimum_spanning_tree(&mut ctx);
+        }
 
         // Build the final layout
         let player_start = if !ctx.rooms.is_empty() {
@@ -89,13 +326,278 @@ impl GraphExecutor {
             .map(|r| vec![RoomGenerator::get_center(r)])
             .unwrap_or_default();
 
-        Ok(DungeonLayout {
+        let mut layout = DungeonLayout {
             rooms: ctx.rooms,
             connections: ctx.connections,
             spawn_points: ctx.spawn_points,
             player_start,
             exits,
-        })
+            metadata: ctx.layout_metadata,
+        };
+        self.apply_post_passes(&mut layout);
+
+        Ok(layout)
+    }
+
+    fn emit_default_room(&self) -> bool {
+        self.parameters
+            .get("emitDefaultRoom")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Deterministic empty room at the origin; draws nothing from the RNG
+    fn default_room() -> GeneratedRoom {
+        GeneratedRoom {
+            id: "room_0".to_string(),
+            room_type: "empty".to_string(),
+            bounds: Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: DEFAULT_ROOM_SIZE,
+                height: DEFAULT_ROOM_SIZE,
+            },
+            tiles: None,
+            entities: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Apply a single node to an existing context, without following any edges
+    ///
+    /// The node runs inside a graph containing only itself, so flow-control
+    /// nodes (Branch, Loop, ...) have no targets and only their own effect shows.
+    pub fn preview_node(
+        &mut self,
+        node: &GraphNode,
+        ctx: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        self.started = Instant::now();
+        let graph = NodeGraph {
+            nodes: vec![node.clone()],
+            edges: vec![],
+            groups: vec![],
+        };
+        self.execute_node(&node.id, &graph, ctx)
+    }
+
+    /// Execute a Loot generator graph, producing items without any rooms
+    ///
+    /// Only loot-producing nodes (LootDrop, Table) and flow control nodes run;
+    /// spatial nodes such as Room are ignored.
+    pub fn execute_loot(&mut self, generator: &Generator) -> Result<LootResult, ExecutionError> {
+        self.started = Instant::now();
+        self.executions_by_type.clear();
+        let graph = &generator.graph;
+        let mut items = Vec::new();
+        let mut executions = 0u32;
+
+        let start_node = Self::find_start_node(graph)?;
+
+        let outcome = self.execute_loot_node(&start_node.id, graph, &mut items, &mut executions);
+        self.executions = executions;
+        outcome?;
+
+        Ok(LootResult { items })
+    }
+
+    fn execute_loot_node(
+        &mut self,
+        node_id: &str,
+        graph: &crate::models::generator::NodeGraph,
+        items: &mut Vec<LootItem>,
+        executions: &mut u32,
+    ) -> Result<(), ExecutionError> {
+        let node = graph
+            .nodes
+            .iter()
+            .find(|n| n.id == node_id)
+            .ok_or_else(|| ExecutionError::NodeNotFound(node_id.to_string()))?;
+
+        *executions += 1;
+        *self
+            .executions_by_type
+            .entry(node.node_type.clone())
+            .or_default() += 1;
+        if *executions > MAX_NODE_EXECUTIONS {
+            return Err(ExecutionError::ExecutionLimitExceeded);
+        }
+        self.check_timeout(*executions, 0)?;
+
+        let outgoing_edges = self.find_outgoing_edges(node_id, &graph.edges);
+        match &node.node_type {
+            NodeType::Output => return Ok(()),
+            NodeType::LootDrop => self.roll_loot_drop(node, items, *executions)?,
+            NodeType::Table => self.roll_loot_table(node, items, *executions)?,
+            NodeType::RandomSelect => {
+                if let Some(selected) = self.select_edge(&outgoing_edges) {
+                    let target = &outgoing_edges[selected].target.node_id;
+                    self.execute_loot_node(target, graph, items, executions)?;
+                }
+                return Ok(());
+            }
+            NodeType::Loop => {
+                let iterations = node
+                    .data
+                    .extra
+                    .get("iterations")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(3);
+                for _ in 0..iterations {
+                    // Iterations without body nodes never reach the check above
+                    self.check_timeout(*executions, 0)?;
+                    for edge in outgoing_edges
+                        .iter()
+                        .filter(|e| e.target.node_id != node.id)
+                    {
+                        self.execute_loot_node(&edge.target.node_id, graph, items, executions)?;
+                    }
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        for edge in outgoing_edges {
+            self.execute_loot_node(&edge.target.node_id, graph, items, executions)?;
+        }
+
+        Ok(())
+    }
+
+    /// Roll a LootDrop node's `itemCount` items, stopping if the timeout runs out
+    fn roll_loot_drop(
+        &mut self,
+        node: &GraphNode,
+        items: &mut Vec<LootItem>,
+        executions: u32,
+    ) -> Result<(), ExecutionError> {
+        let extra = &node.data.extra;
+        let item_count = extra.get("itemCount").and_then(|v| v.as_u64()).unwrap_or(1);
+        let item_type = extra
+            .get("itemType")
+            .and_then(|v| v.as_str())
+            .unwrap_or("item");
+        let min_quantity = extra
+            .get("minQuantity")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1) as u32;
+        let max_quantity = extra
+            .get("maxQuantity")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or(min_quantity)
+            .max(min_quantity);
+
+        for _ in 0..item_count {
+            self.check_timeout(executions, 0)?;
+            let rarity = match extra.get("rarity").and_then(|v| v.as_str()) {
+                Some(r) => r.to_string(),
+                None => self.roll_rarity(),
+            };
+            let quantity = self.rng.gen_range(min_quantity..=max_quantity);
+            items.push(LootItem {
+                id: format!("item_{}", items.len()),
+                item_type: item_type.to_string(),
+                rarity,
+                quantity,
+            });
+        }
+        Ok(())
+    }
+
+    /// Roll a Table node: `entries` is a list of `{ item, weight, rarity, quantity }`.
+    ///
+    /// Stops if the timeout runs out partway through `rolls`.
+    fn roll_loot_table(
+        &mut self,
+        node: &GraphNode,
+        items: &mut Vec<LootItem>,
+        executions: u32,
+    ) -> Result<(), ExecutionError> {
+        let extra = &node.data.extra;
+        let entries = match extra.get("entries").and_then(|v| v.as_array()) {
+            Some(entries) if !entries.is_empty() => entries,
+            _ => return Ok(()),
+        };
+        let rolls = extra.get("rolls").and_then(|v| v.as_u64()).unwrap_or(1);
+
+        let weights = scale_weights(
+            entries
+                .iter()
+                .map(|e| e.get("weight").and_then(|w| w.as_f64()).unwrap_or(1.0)),
+        );
+
+        for _ in 0..rolls {
+            self.check_timeout(executions, 0)?;
+            let Some(index) = pick_weighted(&mut self.rng, &weights) else {
+                return Ok(());
+            };
+            let entry = &entries[index];
+            let rarity = match entry.get("rarity").and_then(|v| v.as_str()) {
+                Some(r) => r.to_string(),
+                None => self.roll_rarity(),
+            };
+            items.push(LootItem {
+                id: format!("item_{}", items.len()),
+                item_type: entry
+                    .get("item")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("item")
+                    .to_string(),
+                rarity,
+                quantity: entry.get("quantity").and_then(|v| v.as_u64()).unwrap_or(1) as u32,
+            });
+        }
+        Ok(())
+    }
+
+    fn roll_rarity(&mut self) -> String {
+        let roll = self.rng.gen_range(0..100);
+        match roll {
+            0..=59 => "common",
+            60..=84 => "uncommon",
+            85..=94 => "rare",
+            _ => "legendary",
+        }
+        .to_string()
+    }
+
+    /// Run the opt-in layout post-passes configured through parameters
+    fn apply_post_passes(&mut self, layout: &mut DungeonLayout) {
+        if let Some(min_loot) = self
+            .parameters
+            .get("minCriticalLoot")
+            .and_then(|v| v.as_u64())
+        {
+            postprocess::ensure_critical_loot(layout, &mut self.rng, min_loot as usize);
+        }
+        if let Some(gap) = self.parameters.get("minRoomGap").and_then(|v| v.as_f64()) {
+            postprocess::enforce_min_room_gap(layout, gap);
+        }
+        if let Some(length) = self
+            .parameters
+            .get("minCorridorLength")
+            .and_then(|v| v.as_f64())
+        {
+            postprocess::enforce_min_corridor_length(layout, length);
+        }
+        if self
+            .parameters
+            .get("markersAsSpawns")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            postprocess::add_marker_spawns(layout);
+        }
+        // Rounding runs last so earlier passes work at full precision
+        if let Some(decimals) = self
+            .parameters
+            .get("coordPrecision")
+            .and_then(|v| v.as_u64())
+        {
+            postprocess::round_coordinates(layout, decimals);
+        }
     }
 
     /// Execute a single node and follow its outgoing edges
@@ -104,18 +606,69 @@ impl GraphExecutor {
         node_id: &str,
         graph: &crate::models::generator::NodeGraph,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), ExecutionError> {
         let node = graph
             .nodes
             .iter()
             .find(|n| n.id == node_id)
-            .ok_or_else(|| format!("Node {} not found", node_id))?;
+            .ok_or_else(|| ExecutionError::NodeNotFound(node_id.to_string()))?;
+
+        // Re-entering a node on the current path is a cycle; only one passing
+        // through a Loop or RandomSelect can end
+        if let Some(start) = self.path.iter().position(|(id, _)| id == node_id) {
+            if !self.path[start..].iter().any(|(_, may_repeat)| *may_repeat) {
+                let mut cycle: Vec<String> = self.path[start..]
+                    .iter()
+                    .map(|(id, _)| id.clone())
+                    .collect();
+                cycle.push(node_id.to_string());
+                return Err(ExecutionError::Cycle(cycle));
+            }
+        }
+
+        let may_repeat = matches!(node.node_type, NodeType::Loop | NodeType::RandomSelect);
+        self.path.push((node.id.clone(), may_repeat));
+        // Each visit gets its own ChaCha stream of the node's generator, so
+        // repeated visits differ and nested re-entry never shares a stream
+        let mut node_rng = self.rng_for_node(&node.id);
+        let visit = self.node_visits.entry(node.id.clone()).or_default();
+        node_rng.set_stream(*visit);
+        *visit += 1;
+        let outer_rng = std::mem::replace(&mut self.rng, node_rng);
+        let result = self.visit_node(node, graph, ctx);
+        let node_rng = std::mem::replace(&mut self.rng, outer_rng);
+        self.node_draws += node_rng.get_word_pos() as u64;
+        self.path.pop();
+        result
+    }
 
+    fn visit_node(
+        &mut self,
+        node: &GraphNode,
+        graph: &crate::models::generator::NodeGraph,
+        ctx: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
         ctx.node_executions += 1;
+        *self
+            .executions_by_type
+            .entry(node.node_type.clone())
+            .or_default() += 1;
+
+        // Backstop for cycles that do pass through a Loop or RandomSelect
+        if ctx.node_executions > MAX_NODE_EXECUTIONS {
+            return Err(ExecutionError::ExecutionLimitExceeded);
+        }
+        self.check_timeout(ctx.node_executions, ctx.rooms.len())?;
 
-        // Prevent infinite loops
-        if ctx.node_executions > 1000 {
-            return Err("Maximum node executions exceeded (possible infinite loop)".to_string());
+        // Any node may pass authoring info through; later nodes win on key clashes
+        if let Some(metadata) = node
+            .data
+            .extra
+            .get("layoutMetadata")
+            .and_then(|v| v.as_object())
+        {
+            ctx.layout_metadata
+                .extend(metadata.iter().map(|(k, v)| (k.clone(), v.clone())));
         }
 
         // Execute the node based on its type
@@ -138,7 +691,21 @@ impl GraphExecutor {
                 return Ok(()); // Branch handles its own connections
             }
             NodeType::Merge => {
-                // Merge node - just continue (paths join here)
+                // Paths join here: continue once, when the last inbound edge that
+                // can still deliver arrives
+                let key = merge_key(&node.id);
+                let arrived = ctx
+                    .variables
+                    .get(&key)
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0)
+                    + 1;
+                if arrived < self.live_inbound_edges(&node.id, graph) {
+                    ctx.variables.insert(key, serde_json::json!(arrived));
+                    return Ok(());
+                }
+                // Reset so a Loop re-entering the join waits for every branch again
+                ctx.variables.remove(&key);
             }
             NodeType::SpawnPoint => {
                 self.execute_spawn_point_node(node, ctx)?;
@@ -149,6 +716,12 @@ impl GraphExecutor {
             NodeType::LootDrop => {
                 self.execute_loot_drop_node(node, ctx)?;
             }
+            NodeType::Prop => {
+                self.execute_prop_node(node, ctx)?;
+            }
+            NodeType::Distribution => {
+                self.execute_distribution_node(node, ctx);
+            }
             NodeType::RandomSelect => {
                 self.execute_random_select_node(node, graph, ctx)?;
                 return Ok(()); // RandomSelect handles its own connections
@@ -161,13 +734,17 @@ impl GraphExecutor {
                 self.execute_loop_node(node, graph, ctx)?;
                 return Ok(()); // Loop handles its own connections
             }
+            NodeType::Condition => {
+                self.execute_condition_node(node, graph, ctx)?;
+                return Ok(()); // Condition follows only the matching branch
+            }
             _ => {
                 // Unknown node type - skip
             }
         }
 
         // Find and execute connected nodes (follow edges)
-        let outgoing_edges = self.find_outgoing_edges(node_id, &graph.edges);
+        let outgoing_edges = self.find_outgoing_edges(&node.id, &graph.edges);
         for edge in outgoing_edges {
             self.execute_node(&edge.target.node_id, graph, ctx)?;
         }
@@ -179,16 +756,30 @@ impl GraphExecutor {
         &mut self,
         node: &GraphNode,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
-        let config = self.extract_room_config(&node.data.extra);
+    ) -> Result<(), ExecutionError> {
+        let mut config = self.extract_room_config(&node.data.extra, ctx);
+        if let Some(room_type) = depth_room_type(&node.data.extra, ctx.depth) {
+            config.room_type = room_type;
+        }
         let room_id = format!("room_{}", ctx.rooms.len());
 
-        let room = RoomGenerator::generate(
+        let mut room = RoomGenerator::generate(
             &mut self.rng,
             &config,
             ctx.current_position.clone(),
             &room_id,
         );
+        let placed: Vec<&GeneratedRoom> = ctx.rooms.iter().collect();
+        let conflict = self.resolve_overlap(&mut room, &placed, ctx.current_direction);
+        if conflict.is_none_or(|c| c.strategy != OverlapStrategy::Skip) {
+            self.fit_in_bounds(&mut room, &placed)?;
+        }
+        if let Some(conflict) = conflict {
+            record_overlap(ctx, &node.id, &room.id, conflict);
+            if conflict.strategy == OverlapStrategy::Skip {
+                return Ok(());
+            }
+        }
 
         // Connect to previous room if exists
         if let Some(prev_room) = ctx.rooms.last() {
@@ -208,6 +799,21 @@ impl GraphExecutor {
             });
         }
 
+        // Occasionally turn so layouts without branches still wind
+        let turn_chance = self
+            .parameters
+            .get("turnChance")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0)
+            .clamp(0.0, 1.0);
+        if turn_chance > 0.0 && self.rng.gen_bool(turn_chance) {
+            ctx.current_direction = ctx.current_direction.turned(self.rng.gen_bool(0.5));
+            ctx.current_position = LayoutPosition {
+                x: room.bounds.x,
+                y: room.bounds.y,
+            };
+        }
+
         // Update current position for next room
         let spacing = self.rng.gen_range(3.0..8.0);
         match ctx.current_direction {
@@ -222,6 +828,7 @@ impl GraphExecutor {
         }
 
         ctx.rooms.push(room);
+        ctx.depth += 1;
         Ok(())
     }
 
@@ -229,8 +836,8 @@ impl GraphExecutor {
         &mut self,
         node: &GraphNode,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
-        let config = self.extract_room_config(&node.data.extra);
+    ) -> Result<(), ExecutionError> {
+        let config = self.extract_room_config(&node.data.extra, ctx);
         let count = node
             .data
             .extra
@@ -255,6 +862,25 @@ impl GraphExecutor {
             &base_id,
             linear,
         );
+        let mut fitted: Vec<GeneratedRoom> = Vec::with_capacity(chain_rooms.len());
+        for mut room in chain_rooms {
+            let placed: Vec<&GeneratedRoom> = ctx.rooms.iter().chain(fitted.iter()).collect();
+            let conflict = self.resolve_overlap(&mut room, &placed, ctx.current_direction);
+            let skip = conflict.is_some_and(|c| c.strategy == OverlapStrategy::Skip);
+            if !skip {
+                self.fit_in_bounds(&mut room, &placed)?;
+            }
+            if let Some(conflict) = conflict {
+                record_overlap(ctx, &node.id, &room.id, conflict);
+            }
+            if !skip {
+                fitted.push(room);
+            }
+        }
+        let chain_rooms = fitted;
+        if chain_rooms.is_empty() {
+            return Ok(());
+        }
 
         // Connect chain to previous room
         if let (Some(prev_room), Some(first_chain_room)) = (ctx.rooms.last(), chain_rooms.first()) {
@@ -309,6 +935,7 @@ impl GraphExecutor {
             }
         }
 
+        ctx.depth += chain_rooms.len() as u32;
         ctx.rooms.extend(chain_rooms);
         Ok(())
     }
@@ -318,27 +945,52 @@ impl GraphExecutor {
         node: &GraphNode,
         graph: &crate::models::generator::NodeGraph,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), ExecutionError> {
         let outgoing_edges = self.find_outgoing_edges(&node.id, &graph.edges);
         let original_pos = ctx.current_position.clone();
         let original_dir = ctx.current_direction;
+        let original_depth = ctx.depth;
+        // Optional per-edge directions; edges past the end (or unparseable
+        // entries) fall back to the Right/Down/Left/Up cycle
+        let directions: Vec<Option<Direction>> = node
+            .data
+            .extra
+            .get("directions")
+            .and_then(|v| v.as_array())
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|n| n.as_str().and_then(Direction::from_name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let spacing = node
+            .data
+            .extra
+            .get("offset")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(DEFAULT_BRANCH_OFFSET);
 
         // Execute each branch
         for (i, edge) in outgoing_edges.iter().enumerate() {
             // Each branch gets a different direction
-            ctx.current_direction = match i % 4 {
+            ctx.current_direction = directions.get(i).copied().flatten().unwrap_or(match i % 4 {
                 0 => Direction::Right,
                 1 => Direction::Down,
                 2 => Direction::Left,
                 _ => Direction::Up,
-            };
+            });
             ctx.current_position = original_pos.clone();
-
-            // Offset starting position based on branch
-            let offset = (i as f64) * 15.0;
-            match ctx.current_direction {
-                Direction::Right | Direction::Left => ctx.current_position.y += offset,
-                Direction::Up | Direction::Down => ctx.current_position.x += offset,
+            ctx.depth = original_depth;
+
+            // Offset each branch to its own right-hand side, so the four
+            // directions pinwheel apart instead of all shifting the same way
+            let offset = (i as f64) * spacing;
+            match ctx.current_direction.turned(true) {
+                Direction::Right => ctx.current_position.x += offset,
+                Direction::Left => ctx.current_position.x -= offset,
+                Direction::Down => ctx.current_position.y += offset,
+                Direction::Up => ctx.current_position.y -= offset,
             }
 
             self.execute_node(&edge.target.node_id, graph, ctx)?;
@@ -346,6 +998,7 @@ impl GraphExecutor {
 
         // Restore original direction
         ctx.current_direction = original_dir;
+        ctx.depth = original_depth;
         Ok(())
     }
 
@@ -353,7 +1006,7 @@ impl GraphExecutor {
         &mut self,
         node: &GraphNode,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), ExecutionError> {
         let spawn_type = node
             .data
             .extra
@@ -382,16 +1035,119 @@ impl GraphExecutor {
         &mut self,
         node: &GraphNode,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
-        let enemy_count = node
-            .data
-            .extra
+    ) -> Result<(), ExecutionError> {
+        let extra = &node.data.extra;
+        let enemy_count = extra
             .get("enemyCount")
+            .and_then(|v| self.resolve_number(v, ctx))
+            .map_or(2, |v| v.max(0.0).round() as usize);
+        let clustering = extra
+            .get("clustering")
+            .and_then(|v| self.resolve_number(v, ctx))
+            .unwrap_or(0.0);
+
+        if let Some(room) = ctx.rooms.last_mut() {
+            let first_new = room.entities.len();
+            RoomGenerator::add_entities(
+                &mut self.rng,
+                room,
+                "enemy",
+                enemy_count,
+                enemy_count + 2,
+                clustering,
+            );
+            apply_rotation(
+                &node.data.extra,
+                &mut room.entities[first_new..],
+                &mut self.rng,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Sample a value and store it in `ctx.variables` under the node's `output` (default: its id).
+    ///
+    /// `kind` is `"uniform"` (`min`..=`max`, default 0..=1), `"normal"` (`mean`,
+    /// `stddev`, default 0 and 1, clamped to `min`/`max` when given) or
+    /// `"weighted"` (`choices` of `{ value, weight }`, where `value` may be any
+    /// JSON). A weighted node without positive weights, or a uniform one whose
+    /// range isn't finite, stores nothing.
+    fn execute_distribution_node(&mut self, node: &GraphNode, ctx: &mut ExecutionContext) {
+        let extra = &node.data.extra;
+        let number = |key: &str| extra.get(key).and_then(|v| v.as_f64());
+        let kind = extra
+            .get("kind")
+            .and_then(|v| v.as_str())
+            .unwrap_or("uniform");
+
+        let value = match kind.to_lowercase().as_str() {
+            "normal" | "gaussian" => {
+                let mean = number("mean").unwrap_or(0.0);
+                let stddev = number("stddev").unwrap_or(1.0).max(0.0);
+                let sample = mean + standard_normal(&mut self.rng) * stddev;
+                let sample = number("min").map_or(sample, |min| sample.max(min));
+                let sample = number("max").map_or(sample, |max| sample.min(max));
+                serde_json::json!(sample)
+            }
+            "weighted" => {
+                let Some(choices) = extra.get("choices").and_then(|v| v.as_array()) else {
+                    return;
+                };
+                let weights = scale_weights(
+                    choices
+                        .iter()
+                        .map(|c| c.get("weight").and_then(|w| w.as_f64()).unwrap_or(1.0)),
+                );
+                let Some(index) = pick_weighted(&mut self.rng, &weights) else {
+                    return;
+                };
+                choices[index]
+                    .get("value")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            _ => {
+                let (a, b) = (number("min").unwrap_or(0.0), number("max").unwrap_or(1.0));
+                let Some(sample) = uniform(&mut self.rng, a.min(b), a.max(b)) else {
+                    return;
+                };
+                serde_json::json!(sample)
+            }
+        };
+
+        let output = extra
+            .get("output")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&node.id);
+        ctx.variables.insert(output.to_string(), value);
+    }
+
+    fn execute_prop_node(
+        &mut self,
+        node: &GraphNode,
+        ctx: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        let extra = &node.data.extra;
+        let count = extra
+            .get("count")
+            .or_else(|| extra.get("propCount"))
             .and_then(|v| v.as_u64())
-            .unwrap_or(2) as usize;
+            .unwrap_or(1) as usize;
+        let prop_type = extra
+            .get("propType")
+            .and_then(|v| v.as_str())
+            .unwrap_or("prop");
+        let placement = extra
+            .get("placement")
+            .and_then(|v| v.as_str())
+            .map(PropPlacement::from)
+            .unwrap_or(PropPlacement::Wall);
 
         if let Some(room) = ctx.rooms.last_mut() {
-            RoomGenerator::add_entities(&mut self.rng, room, "enemy", enemy_count, enemy_count + 2);
+            let first_new = room.entities.len();
+            RoomGenerator::add_props(&mut self.rng, room, prop_type, count, placement);
+            apply_rotation(extra, &mut room.entities[first_new..], &mut self.rng);
         }
 
         Ok(())
@@ -401,7 +1157,7 @@ impl GraphExecutor {
         &mut self,
         node: &GraphNode,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), ExecutionError> {
         let item_count = node
             .data
             .extra
@@ -410,7 +1166,14 @@ impl GraphExecutor {
             .unwrap_or(1) as usize;
 
         if let Some(room) = ctx.rooms.last_mut() {
-            RoomGenerator::add_entities(&mut self.rng, room, "loot", item_count, item_count + 1);
+            RoomGenerator::add_entities(
+                &mut self.rng,
+                room,
+                "loot",
+                item_count,
+                item_count + 1,
+                0.0,
+            );
         }
 
         Ok(())
@@ -421,25 +1184,113 @@ impl GraphExecutor {
         node: &GraphNode,
         graph: &crate::models::generator::NodeGraph,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), ExecutionError> {
         let outgoing_edges = self.find_outgoing_edges(&node.id, &graph.edges);
-        if outgoing_edges.is_empty() {
-            return Ok(());
+
+        let selected = self.select_edge(&outgoing_edges);
+        self.skipped_edges.extend(
+            outgoing_edges
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| Some(i) != selected)
+                .map(|(_, e)| e.id.clone()),
+        );
+        if let Some(selected) = selected {
+            self.execute_node(&outgoing_edges[selected].target.node_id, graph, ctx)?;
+        }
+
+        self.release_merges(graph, ctx)
+    }
+
+    /// Inbound edges of a Merge that have delivered or still can: not declined
+    /// by a RandomSelect or Condition, from a node reachable from Start without
+    /// crossing a declined edge or the Merge itself
+    fn live_inbound_edges(&self, merge_id: &str, graph: &NodeGraph) -> u64 {
+        let mut reachable: HashSet<&str> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == NodeType::Start)
+            .map(|n| n.id.as_str())
+            .collect();
+        let mut frontier: Vec<&str> = reachable.iter().copied().collect();
+        while let Some(current) = frontier.pop() {
+            for edge in &graph.edges {
+                let target = edge.target.node_id.as_str();
+                if edge.source.node_id == current
+                    && target != merge_id
+                    && !self.skipped_edges.contains(&edge.id)
+                    && reachable.insert(target)
+                {
+                    frontier.push(target);
+                }
+            }
         }
 
-        // Pick a random edge to follow
-        let selected = self.rng.gen_range(0..outgoing_edges.len());
-        self.execute_node(&outgoing_edges[selected].target.node_id, graph, ctx)?;
+        graph
+            .edges
+            .iter()
+            .filter(|e| e.target.node_id == merge_id)
+            .filter(|e| !self.skipped_edges.contains(&e.id))
+            .filter(|e| reachable.contains(e.source.node_id.as_str()))
+            .count() as u64
+    }
 
+    /// Continue past Merges whose remaining paths were just declined.
+    ///
+    /// Runs after a RandomSelect or Condition has finished its chosen paths, so
+    /// a join left waiting on a path that will now never arrive still proceeds.
+    fn release_merges(
+        &mut self,
+        graph: &NodeGraph,
+        ctx: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        let ready: Vec<String> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == NodeType::Merge)
+            .filter(|n| {
+                let arrived = ctx
+                    .variables
+                    .get(&merge_key(&n.id))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                arrived > 0 && arrived >= self.live_inbound_edges(&n.id, graph)
+            })
+            .map(|n| n.id.clone())
+            .collect();
+        // Re-entering a ready Merge counts as its final arrival
+        for merge_id in ready {
+            self.execute_node(&merge_id, graph, ctx)?;
+        }
         Ok(())
     }
 
+    /// Index of the edge a RandomSelect follows, by `metadata.weight` (missing weights count 1).
+    ///
+    /// Uniform when no edge declares a weight, so unweighted graphs keep their
+    /// draws. `None` when there are no edges or every weight is zero.
+    fn select_edge(&mut self, edges: &[&Edge]) -> Option<usize> {
+        if edges.is_empty() {
+            return None;
+        }
+        let edge_weights: Vec<Option<f64>> = edges
+            .iter()
+            .map(|e| e.metadata.as_ref().and_then(|m| m.weight))
+            .collect();
+        if edge_weights.iter().all(Option::is_none) {
+            return Some(self.rng.gen_range(0..edges.len()));
+        }
+
+        let weights = scale_weights(edge_weights.iter().map(|w| w.unwrap_or(1.0)));
+        pick_weighted(&mut self.rng, &weights)
+    }
+
     fn execute_sequence_node(
         &mut self,
         node: &GraphNode,
         graph: &crate::models::generator::NodeGraph,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), ExecutionError> {
         let outgoing_edges = self.find_outgoing_edges(&node.id, &graph.edges);
 
         // Execute all connected nodes in sequence
@@ -450,12 +1301,16 @@ impl GraphExecutor {
         Ok(())
     }
 
+    /// Run the loop body `iterations` times with `loopIndex` and `loopCount` set in `ctx.variables`.
+    ///
+    /// An enclosing loop's values are restored once this loop finishes, so
+    /// nested loops each see their own index.
     fn execute_loop_node(
         &mut self,
         node: &GraphNode,
         graph: &crate::models::generator::NodeGraph,
         ctx: &mut ExecutionContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), ExecutionError> {
         let iterations = node
             .data
             .extra
@@ -464,46 +1319,191 @@ impl GraphExecutor {
             .unwrap_or(3) as usize;
 
         let outgoing_edges = self.find_outgoing_edges(&node.id, &graph.edges);
-
-        for _ in 0..iterations {
+        // Each iteration makes its own RandomSelect/Condition choices
+        let outer_skipped = self.skipped_edges.clone();
+        let outer_index = ctx.variables.remove(LOOP_INDEX);
+        let outer_count = ctx.variables.remove(LOOP_COUNT);
+        ctx.variables
+            .insert(LOOP_COUNT.to_string(), serde_json::json!(iterations));
+
+        let mut outcome = Ok(());
+        'iterations: for index in 0..iterations {
+            ctx.variables
+                .insert(LOOP_INDEX.to_string(), serde_json::json!(index));
+            self.skipped_edges.clone_from(&outer_skipped);
+            // Iterations without body nodes never reach the check in `visit_node`
+            outcome = self.check_timeout(ctx.node_executions, ctx.rooms.len());
+            if outcome.is_err() {
+                break 'iterations;
+            }
             for edge in &outgoing_edges {
                 // Skip if it's a loop-back edge (target is before source in graph)
                 if edge.target.node_id != node.id {
-                    self.execute_node(&edge.target.node_id, graph, ctx)?;
+                    outcome = self.execute_node(&edge.target.node_id, graph, ctx);
+                    if outcome.is_err() {
+                        break 'iterations;
+                    }
                 }
             }
         }
 
-        Ok(())
+        for (key, outer) in [(LOOP_INDEX, outer_index), (LOOP_COUNT, outer_count)] {
+            match outer {
+                Some(value) => ctx.variables.insert(key.to_string(), value),
+                None => ctx.variables.remove(key),
+            };
+        }
+        self.skipped_edges = outer_skipped;
+        outcome
     }
 
-    fn extract_room_config(&self, extra: &HashMap<String, serde_json::Value>) -> RoomConfig {
-        let mut config = RoomConfig::default();
-
-        if let Some(v) = extra.get("minWidth").and_then(|v| v.as_f64()) {
-            config.min_width = v;
-        }
-        if let Some(v) = extra.get("maxWidth").and_then(|v| v.as_f64()) {
-            config.max_width = v;
-        }
-        if let Some(v) = extra.get("minHeight").and_then(|v| v.as_f64()) {
-            config.min_height = v;
+    /// Follow the outgoing edges labelled `"true"` or `"false"` per the node's `condition`.
+    ///
+    /// The condition is `{ variable, op, value }`, where `variable` is looked up in
+    /// `ctx.variables`, then in derived state (`roomCount`, `connectionCount`,
+    /// `spawnCount`, `depth`), then in the generation parameters. A missing
+    /// variable or malformed condition evaluates to false.
+    fn execute_condition_node(
+        &mut self,
+        node: &GraphNode,
+        graph: &crate::models::generator::NodeGraph,
+        ctx: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        let outcome = node
+            .data
+            .extra
+            .get("condition")
+            .map(|condition| self.evaluate_condition(condition, ctx))
+            .unwrap_or(false);
+        let branch = if outcome { "true" } else { "false" };
+
+        let (taken, declined): (Vec<&Edge>, Vec<&Edge>) = self
+            .find_outgoing_edges(&node.id, &graph.edges)
+            .into_iter()
+            .partition(|e| {
+                e.metadata
+                    .as_ref()
+                    .and_then(|m| m.label.as_deref())
+                    .is_some_and(|label| label.eq_ignore_ascii_case(branch))
+            });
+        self.skipped_edges
+            .extend(declined.iter().map(|e| e.id.clone()));
+        for edge in taken {
+            self.execute_node(&edge.target.node_id, graph, ctx)?;
         }
-        if let Some(v) = extra.get("maxHeight").and_then(|v| v.as_f64()) {
-            config.max_height = v;
+
+        self.release_merges(graph, ctx)
+    }
+
+    fn evaluate_condition(&self, condition: &serde_json::Value, ctx: &ExecutionContext) -> bool {
+        let Some(variable) = condition.get("variable").and_then(|v| v.as_str()) else {
+            return false;
+        };
+        let op = condition.get("op").and_then(|v| v.as_str()).unwrap_or("==");
+        let expected = condition
+            .get("value")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+
+        let Some(actual) = self.lookup_variable(variable, ctx) else {
+            return false;
+        };
+
+        match (actual.as_f64(), expected.as_f64()) {
+            (Some(a), Some(b)) => match op {
+                "==" => a == b,
+                "!=" => a != b,
+                ">" => a > b,
+                ">=" => a >= b,
+                "<" => a < b,
+                "<=" => a <= b,
+                _ => false,
+            },
+            _ => match op {
+                "==" => actual == expected,
+                "!=" => actual != expected,
+                _ => false,
+            },
         }
-        if let Some(v) = extra.get("roomType").and_then(|v| v.as_str()) {
+    }
+
+    /// Value of `variable` from `ctx.variables`, then derived state (`roomCount`,
+    /// `connectionCount`, `spawnCount`, `depth`), then the generation parameters
+    fn lookup_variable(&self, variable: &str, ctx: &ExecutionContext) -> Option<serde_json::Value> {
+        let derived = match variable {
+            "roomCount" => Some(ctx.rooms.len() as u64),
+            "connectionCount" => Some(ctx.connections.len() as u64),
+            "spawnCount" => Some(ctx.spawn_points.len() as u64),
+            "depth" => Some(ctx.depth as u64),
+            _ => None,
+        };
+        ctx.variables
+            .get(variable)
+            .cloned()
+            .or_else(|| derived.map(serde_json::Value::from))
+            .or_else(|| self.parameters.get(variable).cloned())
+    }
+
+    /// A numeric node field: either a number or `{ variable, scale, offset }`,
+    /// read as the variable's value times `scale` (default 1) plus `offset`
+    /// (default 0). Missing variables and non-finite results yield `None`.
+    fn resolve_number(&self, value: &serde_json::Value, ctx: &ExecutionContext) -> Option<f64> {
+        let number = match value.get("variable").and_then(|v| v.as_str()) {
+            Some(variable) => {
+                let factor = |key: &str, default: f64| {
+                    value.get(key).and_then(|v| v.as_f64()).unwrap_or(default)
+                };
+                self.lookup_variable(variable, ctx)?.as_f64()? * factor("scale", 1.0)
+                    + factor("offset", 0.0)
+            }
+            None => value.as_f64()?,
+        };
+        number.is_finite().then_some(number)
+    }
+
+    /// Room settings from a node's fields, where sizes may reference variables
+    /// (see `resolve_number`), with generation parameter overrides applied
+    fn extract_room_config(
+        &self,
+        extra: &HashMap<String, serde_json::Value>,
+        ctx: &ExecutionContext,
+    ) -> RoomConfig {
+        let mut config = RoomConfig::default();
+        let size = |key: &str| extra.get(key).and_then(|v| self.resolve_number(v, ctx));
+
+        if let Some(v) = size("minWidth") {
+            config.min_width = v;
+        }
+        if let Some(v) = size("maxWidth") {
+            config.max_width = v;
+        }
+        if let Some(v) = size("minHeight") {
+            config.min_height = v;
+        }
+        if let Some(v) = size("maxHeight") {
+            config.max_height = v;
+        }
+        if let Some(v) = extra.get("roomType").and_then(|v| v.as_str()) {
             config.room_type = v.to_string();
         }
         if let Some(v) = extra.get("shape").and_then(|v| v.as_str()) {
             config.shape = RoomShape::from(v);
         }
+        if let Some(v) = extra.get("sizeDistribution").and_then(|v| v.as_str()) {
+            config.size_distribution = SizeDistribution::from(v);
+        }
         if let Some(v) = extra.get("tags").and_then(|v| v.as_array()) {
             config.tags = v
                 .iter()
                 .filter_map(|t| t.as_str().map(String::from))
                 .collect();
         }
+        if let Some(v) = extra.get("emitTiles").and_then(|v| v.as_bool()) {
+            config.emit_tiles = v;
+        }
+        if let Some(v) = extra.get("floorVariants").and_then(|v| v.as_array()) {
+            config.floor_variants = v.iter().filter_map(|w| w.as_f64()).collect();
+        }
 
         // Check parameters for overrides
         if let Some(v) = self.parameters.get("minRoomSize").and_then(|v| v.as_f64()) {
@@ -514,10 +1514,178 @@ impl GraphExecutor {
             config.max_width = v;
             config.max_height = v;
         }
+        if let Some(v) = self.parameters.get("emitTiles").and_then(|v| v.as_bool()) {
+            config.emit_tiles = v;
+        }
+
+        // A variable-driven bound can cross its fixed partner; sample between them anyway
+        if config.min_width > config.max_width {
+            std::mem::swap(&mut config.min_width, &mut config.max_width);
+        }
+        if config.min_height > config.max_height {
+            std::mem::swap(&mut config.min_height, &mut config.max_height);
+        }
+        // Sizes that still can't be sampled, like an infinite span, use the defaults
+        if config.validate().is_err() {
+            let defaults = RoomConfig::default();
+            config.min_width = defaults.min_width;
+            config.max_width = defaults.max_width;
+            config.min_height = defaults.min_height;
+            config.max_height = defaults.max_height;
+        }
 
         config
     }
 
+    /// Keep a new room inside `maxBounds`, moving it to the first free spot if needed.
+    ///
+    /// The room is first clamped into the region; if that collides with a placed
+    /// room, the region is scanned row by row in unit steps. Moving a room draws
+    /// no random values, so unbounded runs are unaffected.
+    fn fit_in_bounds(
+        &self,
+        room: &mut GeneratedRoom,
+        placed: &[&GeneratedRoom],
+    ) -> Result<(), ExecutionError> {
+        let Some((width, height)) = self.max_bounds else {
+            return Ok(());
+        };
+        let out_of_bounds = || ExecutionError::OutOfBounds {
+            width,
+            height,
+            rooms: placed.len(),
+        };
+        let (room_w, room_h) = (room.bounds.width, room.bounds.height);
+        if room_w > width || room_h > height {
+            return Err(out_of_bounds());
+        }
+
+        let is_free = |x: f64, y: f64| {
+            let candidate = Rectangle {
+                x,
+                y,
+                width: room_w,
+                height: room_h,
+            };
+            placed
+                .iter()
+                .all(|other| !rectangles_overlap(&candidate, &other.bounds, 0.0))
+        };
+
+        let clamped_x = room.bounds.x.clamp(0.0, width - room_w);
+        let clamped_y = room.bounds.y.clamp(0.0, height - room_h);
+        let spot = if is_free(clamped_x, clamped_y) {
+            Some((clamped_x, clamped_y))
+        } else {
+            let columns = (width - room_w).floor() as usize;
+            let rows = (height - room_h).floor() as usize;
+            (0..=rows)
+                .flat_map(|row| (0..=columns).map(move |col| (col as f64, row as f64)))
+                .find(|&(x, y)| is_free(x, y))
+        };
+
+        let (x, y) = spot.ok_or_else(out_of_bounds)?;
+        RoomGenerator::translate(room, x - room.bounds.x, y - room.bounds.y);
+        Ok(())
+    }
+
+    /// Handle a new room that comes within `min_room_gap` of a placed room, per `overlapStrategy`.
+    ///
+    /// Returns `None` when there is no conflict. Nudging pushes the room along
+    /// `direction`, each time just past the first room it collides with, and
+    /// gives up after `MAX_COLLISION_NUDGES` attempts. No random values are
+    /// drawn, so seeds keep their sequence.
+    fn resolve_overlap(
+        &self,
+        room: &mut GeneratedRoom,
+        placed: &[&GeneratedRoom],
+        direction: Direction,
+    ) -> Option<OverlapConflict> {
+        self.colliding_room(room, placed)?;
+        let strategy = self.overlap_strategy;
+        if strategy != OverlapStrategy::Nudge {
+            return Some(OverlapConflict {
+                strategy,
+                resolved: strategy == OverlapStrategy::Skip,
+            });
+        }
+
+        let gap = self.min_room_gap;
+        for _ in 0..MAX_COLLISION_NUDGES {
+            let Some(other) = self.colliding_room(room, placed) else {
+                return Some(OverlapConflict {
+                    strategy,
+                    resolved: true,
+                });
+            };
+
+            let (b, o) = (&room.bounds, &other.bounds);
+            let (dx, dy) = match direction {
+                Direction::Right => (o.x + o.width + gap - b.x, 0.0),
+                Direction::Left => (o.x - gap - (b.x + b.width), 0.0),
+                Direction::Down => (0.0, o.y + o.height + gap - b.y),
+                Direction::Up => (0.0, o.y - gap - (b.y + b.height)),
+            };
+            RoomGenerator::translate(room, dx, dy);
+        }
+        Some(OverlapConflict {
+            strategy,
+            resolved: self.colliding_room(room, placed).is_none(),
+        })
+    }
+
+    /// First placed room closer than `min_room_gap` to `room`
+    fn colliding_room<'a>(
+        &self,
+        room: &GeneratedRoom,
+        placed: &[&'a GeneratedRoom],
+    ) -> Option<&'a GeneratedRoom> {
+        let gap = self.min_room_gap;
+        let padded = Rectangle {
+            x: room.bounds.x - gap,
+            y: room.bounds.y - gap,
+            width: room.bounds.width + 2.0 * gap,
+            height: room.bounds.height + 2.0 * gap,
+        };
+        // Rooms exactly `gap` apart only touch the padding, which is allowed
+        placed
+            .iter()
+            .find(|other| rectangles_overlap(&padded, &other.bounds, COLLISION_EPSILON))
+            .copied()
+    }
+
+    /// Abort once the wall-clock budget is spent, independent of the node-count guard
+    fn check_timeout(&self, node_executions: u32, rooms: usize) -> Result<(), ExecutionError> {
+        let elapsed = self.started.elapsed();
+        if elapsed >= self.timeout {
+            return Err(ExecutionError::Timeout {
+                elapsed_ms: elapsed.as_millis() as u64,
+                node_executions,
+                rooms,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the graph is well-formed enough to traverse and return its Start node
+    fn find_start_node(
+        graph: &crate::models::generator::NodeGraph,
+    ) -> Result<&GraphNode, ExecutionError> {
+        if graph.nodes.is_empty() {
+            return Err(ExecutionError::EmptyGraph);
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = graph.nodes.iter().find(|n| !seen.insert(n.id.as_str())) {
+            return Err(ExecutionError::DuplicateNodeId(duplicate.id.clone()));
+        }
+        graph
+            .nodes
+            .iter()
+            .find(|n| matches!(n.node_type, NodeType::Start))
+            .ok_or(ExecutionError::MissingStart)
+    }
+
     fn find_outgoing_edges<'a>(&self, node_id: &str, edges: &'a [Edge]) -> Vec<&'a Edge> {
         edges
             .iter()
@@ -526,6 +1694,167 @@ impl GraphExecutor {
     }
 }
 
+/// Context variable counting a Merge node's arrivals while it waits for its other paths
+fn merge_key(node_id: &str) -> String {
+    format!("merge:{}", node_id)
+}
+
+/// Pick a room type from `depthTypeRules` (`[{ minDepth, type }]`) for a room at `depth`.
+///
+/// The matching rule with the highest `minDepth` wins; `None` keeps the node's fixed type.
+fn depth_room_type(extra: &HashMap<String, serde_json::Value>, depth: u32) -> Option<String> {
+    extra
+        .get("depthTypeRules")
+        .and_then(|v| v.as_array())?
+        .iter()
+        .filter_map(|rule| {
+            let min_depth = rule.get("minDepth").and_then(|v| v.as_u64())?;
+            let room_type = rule.get("type").and_then(|v| v.as_str())?;
+            Some((min_depth, room_type))
+        })
+        .filter(|(min_depth, _)| *min_depth <= depth as u64)
+        .max_by_key(|(min_depth, _)| *min_depth)
+        .map(|(_, room_type)| room_type.to_string())
+}
+
+/// Add a connection for every edge of the rooms' minimum spanning tree that is not already linked.
+///
+/// The tree spans room centers with Euclidean weights, so afterwards every room
+/// is reachable from every other. Doors sit on each room's edge facing the
+/// other room's center; no random values are drawn.
+pub fn connect_minimum_spanning_tree(ctx: &mut ExecutionContext) {
+    for (from_id, to_id, _) in layout_graph::rooms_mst(&ctx.rooms) {
+        let linked = ctx.connections.iter().any(|c| {
+            (c.from_room_id == from_id && c.to_room_id == to_id)
+                || (c.from_room_id == to_id && c.to_room_id == from_id)
+        });
+        if linked {
+            continue;
+        }
+        let (Some(from), Some(to)) = (
+            ctx.rooms.iter().find(|r| r.id == from_id),
+            ctx.rooms.iter().find(|r| r.id == to_id),
+        ) else {
+            continue;
+        };
+        let connection = RoomConnection {
+            from_door: facing_door(from, &RoomGenerator::get_center(to)),
+            to_door: facing_door(to, &RoomGenerator::get_center(from)),
+            from_room_id: from_id,
+            to_room_id: to_id,
+        };
+        ctx.connections.push(connection);
+    }
+}
+
+/// Point on `room`'s boundary closest to `target`, or the edge toward it when `target` is inside
+fn facing_door(room: &GeneratedRoom, target: &LayoutPosition) -> LayoutPosition {
+    let b = &room.bounds;
+    let x = target.x.clamp(b.x, b.x + b.width);
+    let y = target.y.clamp(b.y, b.y + b.height);
+    let center = RoomGenerator::get_center(room);
+    let inside = x > b.x && x < b.x + b.width && y > b.y && y < b.y + b.height;
+    if !inside {
+        return LayoutPosition { x, y };
+    }
+    if (target.x - center.x).abs() * b.height >= (target.y - center.y).abs() * b.width {
+        let x = if target.x >= center.x {
+            b.x + b.width
+        } else {
+            b.x
+        };
+        LayoutPosition { x, y: center.y }
+    } else {
+        let y = if target.y >= center.y {
+            b.y + b.height
+        } else {
+            b.y
+        };
+        LayoutPosition { x: center.x, y }
+    }
+}
+
+/// 64-bit FNV-1a hash; stable across platforms and Rust versions, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// A new room that collided with a placed one, and how that was handled
+#[derive(Debug, Clone, Copy)]
+struct OverlapConflict {
+    strategy: OverlapStrategy,
+    /// Whether the layout ends up free of the overlap (always false for `Allow`)
+    resolved: bool,
+}
+
+/// Append a conflict to the layout's `overlapResolutions` metadata list
+fn record_overlap(
+    ctx: &mut ExecutionContext,
+    node_id: &str,
+    room_id: &str,
+    conflict: OverlapConflict,
+) {
+    let entry = serde_json::json!({
+        "node": node_id,
+        "room": room_id,
+        "strategy": conflict.strategy.name(),
+        "resolved": conflict.resolved,
+    });
+    match ctx
+        .layout_metadata
+        .entry("overlapResolutions".to_string())
+        .or_insert_with(|| serde_json::json!([]))
+    {
+        serde_json::Value::Array(entries) => entries.push(entry),
+        other => *other = serde_json::json!([entry]),
+    }
+}
+
+/// Set entity facing from a node's `rotation` (fixed radians) or `rotationRandom`.
+///
+/// `rotationRandom` is either `true` for a full turn or a `[min, max]` range in
+/// radians; a fixed `rotation` wins when both are given. Without either, or with
+/// a range whose span isn't finite, entities keep no rotation and no random
+/// values are drawn.
+fn apply_rotation(
+    extra: &HashMap<String, serde_json::Value>,
+    entities: &mut [PlacedEntity],
+    rng: &mut ChaCha8Rng,
+) {
+    if let Some(rotation) = extra.get("rotation").and_then(|v| v.as_f64()) {
+        for entity in entities {
+            entity.rotation = Some(rotation);
+        }
+        return;
+    }
+
+    let range = match extra.get("rotationRandom") {
+        Some(serde_json::Value::Bool(true)) => (0.0, std::f64::consts::TAU),
+        Some(serde_json::Value::Array(bounds)) => {
+            match (
+                bounds.first().and_then(|v| v.as_f64()),
+                bounds.get(1).and_then(|v| v.as_f64()),
+            ) {
+                (Some(min), Some(max)) if !(max - min).is_finite() => return,
+                (Some(min), Some(max)) if min < max => (min, max),
+                (Some(min), Some(_)) => (min, min),
+                _ => return,
+            }
+        }
+        _ => return,
+    };
+
+    for entity in entities {
+        entity.rotation = Some(if range.0 < range.1 {
+            rng.gen_range(range.0..range.1)
+        } else {
+            range.0
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -627,6 +1956,73 @@ mod tests {
         }
     }
 
+    fn node(id: &str, node_type: NodeType, extra: serde_json::Value) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            node_type,
+            position: Position { x: 0.0, y: 0.0 },
+            data: NodeData {
+                label: id.to_string(),
+                extra: serde_json::from_value(extra).unwrap(),
+            },
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            id: format!("{}->{}", from, to),
+            source: PortRef {
+                node_id: from.to_string(),
+                port_id: "out".to_string(),
+            },
+            target: PortRef {
+                node_id: to.to_string(),
+                port_id: "in".to_string(),
+            },
+            metadata: None,
+        }
+    }
+
+    fn labeled_edge(from: &str, to: &str, label: &str) -> Edge {
+        Edge {
+            metadata: Some(EdgeMetadata {
+                label: Some(label.to_string()),
+                animated: false,
+                weight: None,
+            }),
+            ..edge(from, to)
+        }
+    }
+
+    fn graph_generator(nodes: Vec<GraphNode>, edges: Vec<Edge>) -> Generator {
+        Generator {
+            graph: NodeGraph {
+                nodes,
+                edges,
+                groups: vec![],
+            },
+            ..create_simple_graph()
+        }
+    }
+
+    /// Start -> `count` Room nodes in sequence -> Output
+    fn create_room_sequence_graph(count: usize) -> Generator {
+        let mut nodes = vec![node("start", NodeType::Start, serde_json::json!({}))];
+        let mut edges = vec![];
+        let mut previous = "start".to_string();
+        for i in 0..count {
+            let id = format!("room{}", i);
+            nodes.push(node(&id, NodeType::Room, serde_json::json!({})));
+            edges.push(edge(&previous, &id));
+            previous = id;
+        }
+        nodes.push(node("output", NodeType::Output, serde_json::json!({})));
+        edges.push(edge(&previous, "output"));
+        graph_generator(nodes, edges)
+    }
+
     #[test]
     fn test_simple_generation() {
         let generator = create_simple_graph();
@@ -636,4 +2032,1380 @@ mod tests {
         assert_eq!(result.rooms.len(), 1);
         assert!(result.connections.is_empty()); // Single room has no connections
     }
+
+    #[test]
+    fn test_empty_graph_is_reported_distinctly() {
+        let mut generator = create_simple_graph();
+        generator.graph.nodes.clear();
+        generator.graph.edges.clear();
+
+        let mut executor = GraphExecutor::new(12345, HashMap::new());
+        assert_eq!(
+            executor.execute(&generator).unwrap_err(),
+            ExecutionError::EmptyGraph
+        );
+    }
+
+    #[test]
+    fn test_missing_start_node() {
+        let mut generator = create_simple_graph();
+        generator.graph.nodes.remove(0);
+
+        let mut executor = GraphExecutor::new(12345, HashMap::new());
+        assert_eq!(
+            executor.execute(&generator).unwrap_err(),
+            ExecutionError::MissingStart
+        );
+    }
+
+    #[test]
+    fn test_coord_precision_rounds_output() {
+        let generator = create_simple_graph();
+        let params = HashMap::from([("coordPrecision".to_string(), serde_json::json!(2))]);
+        let mut executor = GraphExecutor::new(12345, params);
+        let layout = executor.execute(&generator).unwrap();
+
+        let mut coords = vec![layout.player_start.x, layout.player_start.y];
+        for room in &layout.rooms {
+            coords.extend([
+                room.bounds.x,
+                room.bounds.y,
+                room.bounds.width,
+                room.bounds.height,
+            ]);
+        }
+        for exit in &layout.exits {
+            coords.extend([exit.x, exit.y]);
+        }
+        for value in coords {
+            let scaled = value * 100.0;
+            assert!(
+                (scaled - scaled.round()).abs() < 1e-6,
+                "{} not rounded",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_turn_chance_zero_keeps_straight_line() {
+        let generator = create_room_sequence_graph(8);
+        let params = HashMap::from([("turnChance".to_string(), serde_json::json!(0.0))]);
+        let layout = GraphExecutor::new(3, params).execute(&generator).unwrap();
+
+        assert_eq!(layout.rooms.len(), 8);
+        assert!(layout.rooms.iter().all(|r| r.bounds.y == 0.0));
+    }
+
+    #[test]
+    fn test_high_turn_chance_changes_direction() {
+        let generator = create_room_sequence_graph(8);
+        let params = HashMap::from([("turnChance".to_string(), serde_json::json!(1.0))]);
+        let layout = GraphExecutor::new(3, params).execute(&generator).unwrap();
+
+        assert!(layout.rooms.iter().any(|r| r.bounds.y != 0.0));
+    }
+
+    #[test]
+    fn test_depth_type_rules_pick_type_by_depth() {
+        let mut generator = create_room_sequence_graph(5);
+        for node in generator
+            .graph
+            .nodes
+            .iter_mut()
+            .filter(|n| matches!(n.node_type, NodeType::Room))
+        {
+            node.data.extra.insert(
+                "depthTypeRules".to_string(),
+                serde_json::json!([
+                    { "minDepth": 0, "type": "corridor" },
+                    { "minDepth": 3, "type": "danger" }
+                ]),
+            );
+        }
+
+        let layout = GraphExecutor::new(9, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+        let types: Vec<&str> = layout.rooms.iter().map(|r| r.room_type.as_str()).collect();
+
+        assert_eq!(
+            types,
+            vec!["corridor", "corridor", "corridor", "danger", "danger"]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_node_id_is_rejected() {
+        let mut generator = create_simple_graph();
+        let mut copy = generator.graph.nodes[1].clone();
+        copy.position.x += 50.0;
+        generator.graph.nodes.push(copy);
+
+        let mut executor = GraphExecutor::new(12345, HashMap::new());
+        assert_eq!(
+            executor.execute(&generator).unwrap_err(),
+            ExecutionError::DuplicateNodeId("room1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_timeout_fires_on_slow_execution() {
+        // A zero budget makes any traversal too slow, so the first check fires
+        let generator = create_room_sequence_graph(20);
+        let params = HashMap::from([("timeoutMs".to_string(), serde_json::json!(0))]);
+        let err = GraphExecutor::new(1, params)
+            .execute(&generator)
+            .unwrap_err();
+
+        match err {
+            ExecutionError::Timeout {
+                node_executions,
+                rooms,
+                ..
+            } => {
+                assert_eq!(node_executions, 1);
+                assert_eq!(rooms, 0);
+            }
+            other => panic!("expected timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_timeout_fires_inside_huge_empty_loop() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "loop",
+                    NodeType::Loop,
+                    serde_json::json!({ "iterations": u64::MAX }),
+                ),
+            ],
+            vec![edge("start", "loop")],
+        );
+        let params = HashMap::from([("timeoutMs".to_string(), serde_json::json!(1))]);
+
+        let err = GraphExecutor::new(1, params.clone())
+            .execute(&generator)
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::Timeout { .. }), "{:?}", err);
+
+        let err = GraphExecutor::new(1, params)
+            .execute_loot(&generator)
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::Timeout { .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn test_timeout_fires_inside_huge_loot_rolls() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "table",
+                    NodeType::Table,
+                    serde_json::json!({ "rolls": u64::MAX, "entries": [{ "item": "gold" }] }),
+                ),
+                node(
+                    "drop",
+                    NodeType::LootDrop,
+                    serde_json::json!({ "itemCount": u64::MAX }),
+                ),
+            ],
+            vec![edge("start", "table"), edge("start", "drop")],
+        );
+
+        for target in ["table", "drop"] {
+            let mut only = generator.clone();
+            only.graph.edges.retain(|e| e.target.node_id == target);
+            let params = HashMap::from([("timeoutMs".to_string(), serde_json::json!(1))]);
+            let err = GraphExecutor::new(1, params)
+                .execute_loot(&only)
+                .unwrap_err();
+            assert!(
+                matches!(err, ExecutionError::Timeout { .. }),
+                "{}: {:?}",
+                target,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_timeout_allows_normal_graphs() {
+        let generator = create_room_sequence_graph(20);
+        let layout = GraphExecutor::new(1, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+
+        assert_eq!(layout.rooms.len(), 20);
+    }
+
+    #[test]
+    fn test_entity_rotation_within_configured_range() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("room", NodeType::Room, serde_json::json!({})),
+                node(
+                    "props",
+                    NodeType::Prop,
+                    serde_json::json!({ "propCount": 6, "rotationRandom": [0.5, 1.5] }),
+                ),
+                node(
+                    "enemies",
+                    NodeType::Encounter,
+                    serde_json::json!({ "enemyCount": 2, "rotation": 3.0 }),
+                ),
+            ],
+            vec![
+                edge("start", "room"),
+                edge("room", "props"),
+                edge("props", "enemies"),
+            ],
+        );
+
+        let layout = GraphExecutor::new(7, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+        let entities = &layout.rooms[0].entities;
+        let props: Vec<_> = entities
+            .iter()
+            .filter(|e| e.entity_type == "prop")
+            .collect();
+        let enemies: Vec<_> = entities
+            .iter()
+            .filter(|e| e.entity_type == "enemy")
+            .collect();
+
+        assert_eq!(props.len(), 6);
+        assert!(props
+            .iter()
+            .all(|e| matches!(e.rotation, Some(r) if (0.5..1.5).contains(&r))));
+        assert!(!enemies.is_empty());
+        assert!(enemies.iter().all(|e| e.rotation == Some(3.0)));
+    }
+
+    #[test]
+    fn test_rotation_range_with_infinite_span_is_ignored() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("room", NodeType::Room, serde_json::json!({})),
+                node(
+                    "props",
+                    NodeType::Prop,
+                    serde_json::json!({ "propCount": 3, "rotationRandom": [-1e308, 1e308] }),
+                ),
+            ],
+            vec![edge("start", "room"), edge("room", "props")],
+        );
+
+        let layout = GraphExecutor::new(7, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+
+        assert_eq!(layout.rooms[0].entities.len(), 3);
+        assert!(layout.rooms[0]
+            .entities
+            .iter()
+            .all(|e| e.rotation.is_none()));
+    }
+
+    #[test]
+    fn test_rng_draws_stable_for_fixed_seed_and_graph() {
+        let generator = create_room_sequence_graph(5);
+        let draws = |seed| {
+            let mut executor = GraphExecutor::new(seed, HashMap::new());
+            executor.execute(&generator).unwrap();
+            executor.rng_draws()
+        };
+
+        assert!(draws(3) > 0);
+        assert_eq!(draws(3), draws(3));
+    }
+
+    #[test]
+    fn test_preview_room_node_on_empty_context() {
+        let room = node("room", NodeType::Room, serde_json::json!({}));
+        let mut ctx = ExecutionContext::default();
+
+        GraphExecutor::new(5, HashMap::new())
+            .preview_node(&room, &mut ctx)
+            .unwrap();
+
+        assert_eq!(ctx.rooms.len(), 1);
+        assert!(ctx.connections.is_empty());
+        assert_eq!(ctx.node_executions, 1);
+    }
+
+    #[test]
+    fn test_max_bounds_keeps_rooms_inside_region() {
+        let generator = create_room_sequence_graph(12);
+        let params = HashMap::from([(
+            "maxBounds".to_string(),
+            serde_json::json!({ "width": 60.0, "height": 60.0 }),
+        )]);
+        let layout = GraphExecutor::new(11, params).execute(&generator).unwrap();
+
+        assert_eq!(layout.rooms.len(), 12);
+        for room in &layout.rooms {
+            assert!(room.bounds.x >= 0.0 && room.bounds.y >= 0.0);
+            assert!(room.bounds.x + room.bounds.width <= 60.0);
+            assert!(room.bounds.y + room.bounds.height <= 60.0);
+        }
+    }
+
+    #[test]
+    fn test_max_bounds_too_small_fails() {
+        let generator = create_room_sequence_graph(12);
+        let params = HashMap::from([(
+            "maxBounds".to_string(),
+            serde_json::json!({ "width": 20.0, "height": 20.0 }),
+        )]);
+        let err = GraphExecutor::new(11, params)
+            .execute(&generator)
+            .unwrap_err();
+
+        assert!(matches!(err, ExecutionError::OutOfBounds { .. }));
+    }
+
+    #[test]
+    fn test_prop_node_places_props_along_walls() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("first", NodeType::Room, serde_json::json!({})),
+                node("last", NodeType::Room, serde_json::json!({})),
+                node(
+                    "torches",
+                    NodeType::Prop,
+                    serde_json::json!({ "propType": "torch", "count": 4 }),
+                ),
+                node("output", NodeType::Output, serde_json::json!({})),
+            ],
+            vec![
+                edge("start", "first"),
+                edge("first", "last"),
+                edge("last", "torches"),
+                edge("torches", "output"),
+            ],
+        );
+
+        let layout = GraphExecutor::new(2, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+
+        assert!(layout.rooms[0].entities.is_empty());
+        let room = &layout.rooms[1];
+        let torches: Vec<_> = room
+            .entities
+            .iter()
+            .filter(|e| e.entity_type == "torch")
+            .collect();
+        assert_eq!(torches.len(), 4);
+        for torch in torches {
+            let b = &room.bounds;
+            let to_wall = (torch.position.x - b.x)
+                .min(b.x + b.width - torch.position.x)
+                .min(torch.position.y - b.y)
+                .min(b.y + b.height - torch.position.y);
+            assert!((to_wall - 0.5).abs() < 1e-9);
+        }
+    }
+
+    /// Start -> two rooms -> Condition, with a "treasure" room on the true
+    /// branch and a "trap" room on the false branch
+    fn condition_graph(condition: serde_json::Value) -> Generator {
+        graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("r1", NodeType::Room, serde_json::json!({})),
+                node("r2", NodeType::Room, serde_json::json!({})),
+                node(
+                    "cond",
+                    NodeType::Condition,
+                    serde_json::json!({ "condition": condition }),
+                ),
+                node(
+                    "yes",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "treasure" }),
+                ),
+                node(
+                    "no",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "trap" }),
+                ),
+            ],
+            vec![
+                edge("start", "r1"),
+                edge("r1", "r2"),
+                edge("r2", "cond"),
+                labeled_edge("cond", "yes", "true"),
+                labeled_edge("cond", "no", "false"),
+            ],
+        )
+    }
+
+    fn last_room_type(generator: &Generator) -> String {
+        let layout = GraphExecutor::new(1, HashMap::new())
+            .execute(generator)
+            .unwrap();
+        assert_eq!(layout.rooms.len(), 3);
+        layout.rooms[2].room_type.clone()
+    }
+
+    #[test]
+    fn test_condition_follows_true_branch() {
+        let generator =
+            condition_graph(serde_json::json!({ "variable": "roomCount", "op": ">=", "value": 2 }));
+        assert_eq!(last_room_type(&generator), "treasure");
+    }
+
+    #[test]
+    fn test_condition_follows_false_branch() {
+        let generator =
+            condition_graph(serde_json::json!({ "variable": "roomCount", "op": ">", "value": 5 }));
+        assert_eq!(last_room_type(&generator), "trap");
+    }
+
+    #[test]
+    fn test_condition_missing_variable_is_false() {
+        let generator =
+            condition_graph(serde_json::json!({ "variable": "keysFound", "op": "==", "value": 1 }));
+        assert_eq!(last_room_type(&generator), "trap");
+    }
+
+    #[test]
+    fn test_emit_default_room_for_roomless_graph() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("output", NodeType::Output, serde_json::json!({})),
+            ],
+            vec![edge("start", "output")],
+        );
+
+        let layout = GraphExecutor::new(1, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+        assert!(layout.rooms.is_empty());
+
+        let params = HashMap::from([("emitDefaultRoom".to_string(), serde_json::json!(true))]);
+        let layout = GraphExecutor::new(1, params).execute(&generator).unwrap();
+        assert_eq!(layout.rooms.len(), 1);
+        assert_eq!(layout.rooms[0].bounds.x, 0.0);
+        assert_eq!(layout.rooms[0].bounds.y, 0.0);
+        assert_eq!(layout.player_start.x, DEFAULT_ROOM_SIZE / 2.0);
+    }
+
+    #[test]
+    fn test_node_executions_counts_each_node() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("room", NodeType::Room, serde_json::json!({})),
+                node("output", NodeType::Output, serde_json::json!({})),
+            ],
+            vec![edge("start", "room"), edge("room", "output")],
+        );
+
+        let mut executor = GraphExecutor::new(1, HashMap::new());
+        executor.execute(&generator).unwrap();
+        assert_eq!(executor.node_executions(), 3);
+    }
+
+    #[test]
+    fn test_dense_branching_graph_has_no_overlapping_rooms() {
+        // Four branches of chains leaving the same hub, each followed by a room
+        let mut nodes = vec![
+            node("start", NodeType::Start, serde_json::json!({})),
+            node("hub", NodeType::Room, serde_json::json!({})),
+            node("branch", NodeType::Branch, serde_json::json!({})),
+        ];
+        let mut edges = vec![edge("start", "hub"), edge("hub", "branch")];
+        for i in 0..4 {
+            let chain = format!("chain{}", i);
+            let tail = format!("tail{}", i);
+            nodes.push(node(
+                &chain,
+                NodeType::RoomChain,
+                serde_json::json!({ "count": 4 }),
+            ));
+            nodes.push(node(&tail, NodeType::Room, serde_json::json!({})));
+            edges.push(edge("branch", &chain));
+            edges.push(edge(&chain, &tail));
+        }
+        let generator = graph_generator(nodes, edges);
+
+        for seed in 0..20 {
+            let layout = GraphExecutor::new(seed, HashMap::new())
+                .execute(&generator)
+                .unwrap();
+            assert_eq!(layout.rooms.len(), 21);
+            for (i, a) in layout.rooms.iter().enumerate() {
+                for b in &layout.rooms[i + 1..] {
+                    assert!(
+                        !rectangles_overlap(&a.bounds, &b.bounds, 0.0),
+                        "seed {}: {} overlaps {}",
+                        seed,
+                        a.id,
+                        b.id
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout_metadata_merged_into_result() {
+        let generator = graph_generator(
+            vec![
+                node(
+                    "start",
+                    NodeType::Start,
+                    serde_json::json!({ "layoutMetadata": { "theme": "crypt", "author": "ana" } }),
+                ),
+                node(
+                    "room",
+                    NodeType::Room,
+                    serde_json::json!({ "layoutMetadata": { "theme": "ice", "difficulty": "hard" } }),
+                ),
+                node("output", NodeType::Output, serde_json::json!({})),
+            ],
+            vec![edge("start", "room"), edge("room", "output")],
+        );
+
+        let layout = GraphExecutor::new(1, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+
+        assert_eq!(layout.metadata.len(), 3);
+        assert_eq!(layout.metadata["theme"], "ice");
+        assert_eq!(layout.metadata["author"], "ana");
+        assert_eq!(layout.metadata["difficulty"], "hard");
+    }
+
+    #[test]
+    fn test_min_corridor_length_lengthens_short_corridors() {
+        let generator = create_room_sequence_graph(8);
+        let params = HashMap::from([("minCorridorLength".to_string(), serde_json::json!(12.0))]);
+
+        for seed in 0..10 {
+            let layout = GraphExecutor::new(seed, params.clone())
+                .execute(&generator)
+                .unwrap();
+            assert_eq!(layout.connections.len(), 7);
+            for conn in &layout.connections {
+                let length = ((conn.to_door.x - conn.from_door.x).powi(2)
+                    + (conn.to_door.y - conn.from_door.y).powi(2))
+                .sqrt();
+                assert!(length >= 12.0 - 1e-6, "seed {}: corridor {}", seed, length);
+            }
+        }
+    }
+
+    #[test]
+    fn test_executions_by_type_counts_loop_bodies() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "loop",
+                    NodeType::Loop,
+                    serde_json::json!({ "iterations": 6 }),
+                ),
+                node("room", NodeType::Room, serde_json::json!({})),
+            ],
+            vec![edge("start", "loop"), edge("loop", "room")],
+        );
+
+        let mut executor = GraphExecutor::new(1, HashMap::new());
+        executor.execute(&generator).unwrap();
+
+        let counts = executor.executions_by_type();
+        assert_eq!(counts[&NodeType::Start], 1);
+        assert_eq!(counts[&NodeType::Loop], 1);
+        assert_eq!(counts[&NodeType::Room], 6);
+        assert_eq!(
+            counts.values().sum::<u64>(),
+            executor.node_executions() as u64
+        );
+    }
+
+    #[test]
+    fn test_branches_in_opposing_directions_do_not_overlap() {
+        // Four branches (right, down, left, up) of three rooms each
+        let mut nodes = vec![
+            node("start", NodeType::Start, serde_json::json!({})),
+            node("branch", NodeType::Branch, serde_json::json!({})),
+        ];
+        let mut edges = vec![edge("start", "branch")];
+        for i in 0..4 {
+            let names: Vec<String> = (0..3).map(|j| format!("room{}_{}", i, j)).collect();
+            for name in &names {
+                nodes.push(node(name, NodeType::Room, serde_json::json!({})));
+            }
+            edges.push(edge("branch", &names[0]));
+            edges.push(edge(&names[0], &names[1]));
+            edges.push(edge(&names[1], &names[2]));
+        }
+        let generator = graph_generator(nodes, edges);
+
+        for seed in 0..20 {
+            let layout = GraphExecutor::new(seed, HashMap::new())
+                .execute(&generator)
+                .unwrap();
+            let branch_bounds: Vec<Rectangle> = layout
+                .rooms
+                .chunks(3)
+                .map(|rooms| {
+                    let left = rooms.iter().map(|r| r.bounds.x).fold(f64::MAX, f64::min);
+                    let top = rooms.iter().map(|r| r.bounds.y).fold(f64::MAX, f64::min);
+                    let right = rooms
+                        .iter()
+                        .map(|r| r.bounds.x + r.bounds.width)
+                        .fold(f64::MIN, f64::max);
+                    let bottom = rooms
+                        .iter()
+                        .map(|r| r.bounds.y + r.bounds.height)
+                        .fold(f64::MIN, f64::max);
+                    Rectangle {
+                        x: left,
+                        y: top,
+                        width: right - left,
+                        height: bottom - top,
+                    }
+                })
+                .collect();
+
+            for (i, a) in branch_bounds.iter().enumerate() {
+                for (j, b) in branch_bounds.iter().enumerate().skip(i + 1) {
+                    assert!(
+                        !rectangles_overlap(a, b, 0.0),
+                        "seed {}: branch {} overlaps branch {}",
+                        seed,
+                        i,
+                        j
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unguarded_cycle_names_its_nodes() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("one", NodeType::Room, serde_json::json!({})),
+                node("two", NodeType::Room, serde_json::json!({})),
+            ],
+            vec![edge("start", "one"), edge("one", "two"), edge("two", "one")],
+        );
+
+        let err = GraphExecutor::new(1, HashMap::new())
+            .execute(&generator)
+            .unwrap_err();
+
+        match &err {
+            ExecutionError::Cycle(nodes) => assert_eq!(nodes, &["one", "two", "one"]),
+            other => panic!("expected a cycle error, got {:?}", other),
+        }
+        assert!(err.to_string().contains("one -> two -> one"));
+    }
+
+    #[test]
+    fn test_large_acyclic_graph_is_not_a_cycle() {
+        // A diamond whose join room is reached twice (never while already on
+        // the path), each time followed by a long chain of rooms
+        let mut nodes = vec![
+            node("start", NodeType::Start, serde_json::json!({})),
+            node("left", NodeType::Merge, serde_json::json!({})),
+            node("right", NodeType::Merge, serde_json::json!({})),
+            node("join", NodeType::Room, serde_json::json!({})),
+        ];
+        let mut edges = vec![
+            edge("start", "left"),
+            edge("start", "right"),
+            edge("left", "join"),
+            edge("right", "join"),
+        ];
+        let mut previous = "join".to_string();
+        for i in 0..200 {
+            let room = format!("room{}", i);
+            nodes.push(node(&room, NodeType::Room, serde_json::json!({})));
+            edges.push(edge(&previous, &room));
+            previous = room;
+        }
+        let generator = graph_generator(nodes, edges);
+
+        let layout = GraphExecutor::new(1, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+        assert_eq!(layout.rooms.len(), 2 * 201);
+    }
+
+    #[test]
+    fn test_merge_runs_downstream_once() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("branch", NodeType::Branch, serde_json::json!({})),
+                node("a", NodeType::Room, serde_json::json!({ "roomType": "a" })),
+                node("b", NodeType::Room, serde_json::json!({ "roomType": "b" })),
+                node("merge", NodeType::Merge, serde_json::json!({})),
+                node(
+                    "after",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "after" }),
+                ),
+                node("output", NodeType::Output, serde_json::json!({})),
+            ],
+            vec![
+                edge("start", "branch"),
+                edge("branch", "a"),
+                edge("branch", "b"),
+                edge("a", "merge"),
+                edge("b", "merge"),
+                edge("merge", "after"),
+                edge("after", "output"),
+            ],
+        );
+
+        let mut executor = GraphExecutor::new(1, HashMap::new());
+        let layout = executor.execute(&generator).unwrap();
+
+        let types: Vec<&str> = layout.rooms.iter().map(|r| r.room_type.as_str()).collect();
+        assert_eq!(types, ["a", "b", "after"]);
+        assert_eq!(executor.executions_by_type()[&NodeType::Output], 1);
+    }
+
+    #[test]
+    fn test_merge_after_random_select_runs_downstream() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("pick", NodeType::RandomSelect, serde_json::json!({})),
+                node("a", NodeType::Room, serde_json::json!({ "roomType": "a" })),
+                node("b", NodeType::Room, serde_json::json!({ "roomType": "b" })),
+                node("merge", NodeType::Merge, serde_json::json!({})),
+                node(
+                    "after",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "after" }),
+                ),
+                node("output", NodeType::Output, serde_json::json!({})),
+            ],
+            vec![
+                edge("start", "pick"),
+                edge("pick", "a"),
+                edge("pick", "b"),
+                edge("a", "merge"),
+                edge("b", "merge"),
+                edge("merge", "after"),
+                edge("after", "output"),
+            ],
+        );
+
+        for seed in 0..8 {
+            let mut executor = GraphExecutor::new(seed, HashMap::new());
+            let layout = executor.execute(&generator).unwrap();
+
+            assert_eq!(layout.rooms.len(), 2);
+            assert_eq!(layout.rooms[1].room_type, "after");
+            assert_eq!(executor.executions_by_type()[&NodeType::Output], 1);
+        }
+    }
+
+    #[test]
+    fn test_merge_waits_for_branches_until_random_select_declines_one() {
+        // branch -> {a -> merge, pick -> {c -> merge, d}}; when pick takes d the
+        // merge is released once pick finishes, after both branches are laid out
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("branch", NodeType::Branch, serde_json::json!({})),
+                node("a", NodeType::Room, serde_json::json!({ "roomType": "a" })),
+                node("pick", NodeType::RandomSelect, serde_json::json!({})),
+                node("c", NodeType::Room, serde_json::json!({ "roomType": "c" })),
+                node("d", NodeType::Room, serde_json::json!({ "roomType": "d" })),
+                node("merge", NodeType::Merge, serde_json::json!({})),
+                node(
+                    "after",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "after" }),
+                ),
+            ],
+            vec![
+                edge("start", "branch"),
+                edge("branch", "a"),
+                edge("branch", "pick"),
+                edge("pick", "c"),
+                edge("pick", "d"),
+                edge("a", "merge"),
+                edge("c", "merge"),
+                edge("merge", "after"),
+            ],
+        );
+
+        let mut picked = HashSet::new();
+        for seed in 0..16 {
+            let layout = GraphExecutor::new(seed, HashMap::new())
+                .execute(&generator)
+                .unwrap();
+            let types: Vec<&str> = layout.rooms.iter().map(|r| r.room_type.as_str()).collect();
+
+            assert_eq!(types.len(), 3, "seed {}: {:?}", seed, types);
+            assert_eq!(types[0], "a");
+            assert_eq!(types[2], "after", "seed {}: {:?}", seed, types);
+            picked.insert(types[1].to_string());
+        }
+        assert_eq!(picked.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_inside_loop_runs_once_per_iteration() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "loop",
+                    NodeType::Loop,
+                    serde_json::json!({ "iterations": 3 }),
+                ),
+                node("branch", NodeType::Branch, serde_json::json!({})),
+                node("a", NodeType::Room, serde_json::json!({ "roomType": "a" })),
+                node("b", NodeType::Room, serde_json::json!({ "roomType": "b" })),
+                node("merge", NodeType::Merge, serde_json::json!({})),
+                node(
+                    "after",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "after" }),
+                ),
+            ],
+            vec![
+                edge("start", "loop"),
+                edge("loop", "branch"),
+                edge("branch", "a"),
+                edge("branch", "b"),
+                edge("a", "merge"),
+                edge("b", "merge"),
+                edge("merge", "after"),
+            ],
+        );
+
+        let layout = GraphExecutor::new(1, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+
+        let after = layout
+            .rooms
+            .iter()
+            .filter(|r| r.room_type == "after")
+            .count();
+        assert_eq!(after, 3);
+    }
+
+    #[test]
+    fn test_markers_as_spawns_mirror_start_and_exit() {
+        let generator = create_room_sequence_graph(3);
+        let parameters = HashMap::from([("markersAsSpawns".to_string(), serde_json::json!(true))]);
+
+        let plain = GraphExecutor::new(5, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+        let layout = GraphExecutor::new(5, parameters)
+            .execute(&generator)
+            .unwrap();
+
+        assert_eq!(layout.spawn_points.len(), plain.spawn_points.len() + 2);
+        let start = layout
+            .spawn_points
+            .iter()
+            .find(|s| s.spawn_type == "player_start")
+            .unwrap();
+        assert_eq!(
+            (start.position.x, start.position.y),
+            (layout.player_start.x, layout.player_start.y)
+        );
+        assert_eq!(start.room_id, layout.rooms[0].id);
+        let exit = layout
+            .spawn_points
+            .iter()
+            .find(|s| s.spawn_type == "exit")
+            .unwrap();
+        assert_eq!(
+            (exit.position.x, exit.position.y),
+            (layout.exits[0].x, layout.exits[0].y)
+        );
+        assert_eq!(exit.room_id, layout.rooms[2].id);
+    }
+
+    #[test]
+    fn test_random_select_follows_edge_weights() {
+        let weighted_edge = |to: &str, weight: f64| Edge {
+            metadata: Some(EdgeMetadata {
+                label: None,
+                animated: false,
+                weight: Some(weight),
+            }),
+            ..edge("pick", to)
+        };
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("pick", NodeType::RandomSelect, serde_json::json!({})),
+                node(
+                    "corridor",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "corridor" }),
+                ),
+                node(
+                    "vault",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "vault" }),
+                ),
+            ],
+            vec![
+                edge("start", "pick"),
+                weighted_edge("corridor", 9.0),
+                weighted_edge("vault", 1.0),
+            ],
+        );
+
+        let runs = 2000;
+        let corridors = (0..runs)
+            .filter(|&seed| {
+                let layout = GraphExecutor::new(seed, HashMap::new())
+                    .execute(&generator)
+                    .unwrap();
+                layout.rooms[0].room_type == "corridor"
+            })
+            .count();
+
+        let share = corridors as f64 / runs as f64;
+        assert!((share - 0.9).abs() < 0.03, "corridor share {}", share);
+    }
+
+    fn overlap_layout(strategy: &str) -> DungeonLayout {
+        // A gap wider than any corridor forces the second room into the first
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node("a", NodeType::Room, serde_json::json!({})),
+                node("b", NodeType::Room, serde_json::json!({})),
+                node("output", NodeType::Output, serde_json::json!({})),
+            ],
+            vec![edge("start", "a"), edge("a", "b"), edge("b", "output")],
+        );
+        let mut params = HashMap::new();
+        params.insert("minRoomGap".to_string(), serde_json::json!(100.0));
+        params.insert("overlapStrategy".to_string(), serde_json::json!(strategy));
+        GraphExecutor::new(3, params).execute(&generator).unwrap()
+    }
+
+    fn overlap_resolutions(layout: &DungeonLayout) -> Vec<serde_json::Value> {
+        layout.metadata["overlapResolutions"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_overlap_strategy_nudge_moves_room_clear() {
+        let layout = overlap_layout("nudge");
+        assert_eq!(layout.rooms.len(), 2);
+        assert!(!rectangles_overlap(
+            &layout.rooms[0].bounds,
+            &layout.rooms[1].bounds,
+            0.0
+        ));
+        let resolutions = overlap_resolutions(&layout);
+        assert_eq!(resolutions.len(), 1);
+        assert_eq!(resolutions[0]["node"], "b");
+        assert_eq!(resolutions[0]["strategy"], "nudge");
+        assert_eq!(resolutions[0]["resolved"], true);
+    }
+
+    #[test]
+    fn test_overlap_strategy_skip_drops_room() {
+        let layout = overlap_layout("skip");
+        assert_eq!(layout.rooms.len(), 1);
+        assert!(layout.connections.is_empty());
+        let resolutions = overlap_resolutions(&layout);
+        assert_eq!(resolutions.len(), 1);
+        assert_eq!(resolutions[0]["strategy"], "skip");
+        assert_eq!(resolutions[0]["resolved"], true);
+    }
+
+    #[test]
+    fn test_overlap_strategy_allow_keeps_room_in_place() {
+        let layout = overlap_layout("allow");
+        assert_eq!(layout.rooms.len(), 2);
+        let resolutions = overlap_resolutions(&layout);
+        assert_eq!(resolutions.len(), 1);
+        assert_eq!(resolutions[0]["strategy"], "allow");
+        assert_eq!(resolutions[0]["resolved"], false);
+    }
+
+    #[test]
+    fn test_ensure_connected_reaches_every_room() {
+        // Branch straight from the start, so each arm begins without a parent room
+        let mut nodes = vec![
+            node("start", NodeType::Start, serde_json::json!({})),
+            node("branch", NodeType::Branch, serde_json::json!({})),
+        ];
+        let mut edges = vec![edge("start", "branch")];
+        for i in 0..3 {
+            let chain = format!("chain{}", i);
+            nodes.push(node(
+                &chain,
+                NodeType::RoomChain,
+                serde_json::json!({ "count": 3 }),
+            ));
+            edges.push(edge("branch", &chain));
+        }
+        let generator = graph_generator(nodes, edges);
+        let mut params = HashMap::new();
+        params.insert("ensureConnected".to_string(), serde_json::json!(true));
+
+        for seed in 0..10 {
+            let layout = GraphExecutor::new(seed, params.clone())
+                .execute(&generator)
+                .unwrap();
+            let graph = layout_graph::LayoutGraph::new(&layout);
+            let distances = graph.distances_from(0);
+            assert_eq!(layout.rooms.len(), 9);
+            assert!(
+                distances.iter().all(|d| d.is_some()),
+                "seed {}: unreachable rooms",
+                seed
+            );
+            for (a, b, _) in layout_graph::room_mst(&layout) {
+                assert!(
+                    layout.connections.iter().any(|c| {
+                        (c.from_room_id == a && c.to_room_id == b)
+                            || (c.from_room_id == b && c.to_room_id == a)
+                    }),
+                    "seed {}: tree edge {} - {} not connected",
+                    seed,
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_loop_variables_drive_room_and_encounter_fields() {
+        let width = serde_json::json!({ "variable": "loopIndex", "scale": 2, "offset": 6 });
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "loop",
+                    NodeType::Loop,
+                    serde_json::json!({ "iterations": 3 }),
+                ),
+                node(
+                    "room",
+                    NodeType::Room,
+                    serde_json::json!({ "minWidth": width, "maxWidth": width }),
+                ),
+                node(
+                    "enemies",
+                    NodeType::Encounter,
+                    serde_json::json!({ "enemyCount": { "variable": "loopCount", "scale": 2 } }),
+                ),
+            ],
+            vec![
+                edge("start", "loop"),
+                edge("loop", "room"),
+                edge("room", "enemies"),
+            ],
+        );
+
+        let layout = GraphExecutor::new(4, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+
+        let widths: Vec<f64> = layout.rooms.iter().map(|r| r.bounds.width).collect();
+        assert_eq!(widths, [6.0, 8.0, 10.0]);
+        for room in &layout.rooms {
+            // enemyCount of 6 rolls between 6 and 8 enemies
+            assert!((6..=8).contains(&room.entities.len()));
+        }
+    }
+
+    #[test]
+    fn test_nested_loops_expose_their_own_index() {
+        // outer(2) -> seq -> [inner(3) -> inner_check, outer_check]; each check
+        // adds a room when its loop is on iteration 1
+        let check = serde_json::json!({
+            "condition": { "variable": "loopIndex", "op": "==", "value": 1 }
+        });
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "outer",
+                    NodeType::Loop,
+                    serde_json::json!({ "iterations": 2 }),
+                ),
+                node("seq", NodeType::Sequence, serde_json::json!({})),
+                node(
+                    "inner",
+                    NodeType::Loop,
+                    serde_json::json!({ "iterations": 3 }),
+                ),
+                node("inner_check", NodeType::Condition, check.clone()),
+                node("outer_check", NodeType::Condition, check),
+                node(
+                    "inner_hit",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "inner" }),
+                ),
+                node(
+                    "outer_hit",
+                    NodeType::Room,
+                    serde_json::json!({ "roomType": "outer" }),
+                ),
+            ],
+            vec![
+                edge("start", "outer"),
+                edge("outer", "seq"),
+                edge("seq", "inner"),
+                edge("seq", "outer_check"),
+                edge("inner", "inner_check"),
+                labeled_edge("inner_check", "inner_hit", "true"),
+                labeled_edge("outer_check", "outer_hit", "true"),
+            ],
+        );
+
+        let mut executor = GraphExecutor::new(1, HashMap::new());
+        let mut ctx = ExecutionContext::default();
+        executor
+            .execute_node("start", &generator.graph, &mut ctx)
+            .unwrap();
+
+        let count = |room_type: &str| {
+            ctx.rooms
+                .iter()
+                .filter(|r| r.room_type == room_type)
+                .count()
+        };
+        assert_eq!(count("inner"), 2);
+        assert_eq!(count("outer"), 1);
+        assert!(!ctx.variables.contains_key("loopIndex"));
+        assert!(!ctx.variables.contains_key("loopCount"));
+    }
+
+    #[test]
+    fn test_branch_directions_from_node_data() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "branch",
+                    NodeType::Branch,
+                    serde_json::json!({ "directions": ["up", "down"], "offset": 0.0 }),
+                ),
+                node("up1", NodeType::Room, serde_json::json!({})),
+                node("up2", NodeType::Room, serde_json::json!({})),
+                node("down1", NodeType::Room, serde_json::json!({})),
+                node("down2", NodeType::Room, serde_json::json!({})),
+            ],
+            vec![
+                edge("start", "branch"),
+                edge("branch", "up1"),
+                edge("up1", "up2"),
+                edge("branch", "down1"),
+                edge("down1", "down2"),
+            ],
+        );
+
+        for seed in 0..10 {
+            let layout = GraphExecutor::new(seed, HashMap::new())
+                .execute(&generator)
+                .unwrap();
+            let [up1, up2, down1, down2] = &layout.rooms[..] else {
+                panic!("expected four rooms, got {}", layout.rooms.len());
+            };
+            assert!(up2.bounds.y + up2.bounds.height <= up1.bounds.y);
+            assert!(down2.bounds.y >= down1.bounds.y + down1.bounds.height);
+            assert!(down1.bounds.y > up1.bounds.y);
+        }
+    }
+
+    #[test]
+    fn test_editing_one_node_keeps_other_rooms_stable() {
+        let generator = |first_room: serde_json::Value| {
+            graph_generator(
+                vec![
+                    node("start", NodeType::Start, serde_json::json!({})),
+                    node("a", NodeType::Room, first_room),
+                    node("b", NodeType::Room, serde_json::json!({})),
+                    node("c", NodeType::Room, serde_json::json!({})),
+                ],
+                vec![edge("start", "a"), edge("a", "b"), edge("b", "c")],
+            )
+        };
+        let original = generator(serde_json::json!({}));
+        let edited = generator(serde_json::json!({ "minWidth": 20.0, "maxWidth": 30.0 }));
+
+        for seed in 0..10 {
+            let before = GraphExecutor::new(seed, HashMap::new())
+                .execute(&original)
+                .unwrap();
+            let after = GraphExecutor::new(seed, HashMap::new())
+                .execute(&edited)
+                .unwrap();
+            assert!(after.rooms[0].bounds.width >= 20.0);
+            for i in 1..3 {
+                let (x, y) = (&before.rooms[i].bounds, &after.rooms[i].bounds);
+                assert_eq!((x.width, x.height), (y.width, y.height), "seed {}", seed);
+            }
+        }
+    }
+
+    /// `samples` values of one Distribution node, each from a fresh visit
+    fn sample_distribution(data: serde_json::Value, samples: usize) -> Vec<serde_json::Value> {
+        let node = node("dist", NodeType::Distribution, data);
+        let mut executor = GraphExecutor::new(11, HashMap::new());
+        (0..samples)
+            .map(|_| {
+                let mut ctx = ExecutionContext::default();
+                executor.preview_node(&node, &mut ctx).unwrap();
+                ctx.variables["size"].clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_room_with_infinite_size_span_uses_default_sizes() {
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "room",
+                    NodeType::Room,
+                    serde_json::json!({ "minWidth": -1e308, "maxWidth": 1e308 }),
+                ),
+            ],
+            vec![edge("start", "room")],
+        );
+
+        let layout = GraphExecutor::new(3, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+
+        let width = layout.rooms[0].bounds.width;
+        assert!((5.0..=10.0).contains(&width), "width {}", width);
+    }
+
+    #[test]
+    fn test_uniform_distribution_with_infinite_span_stores_nothing() {
+        let node = node(
+            "dist",
+            NodeType::Distribution,
+            serde_json::json!({ "kind": "uniform", "min": -1e308, "max": 1e308 }),
+        );
+        let mut ctx = ExecutionContext::default();
+        GraphExecutor::new(1, HashMap::new())
+            .preview_node(&node, &mut ctx)
+            .unwrap();
+
+        assert!(!ctx.variables.contains_key("dist"));
+    }
+
+    #[test]
+    fn test_distribution_output_sizes_room() {
+        let size = serde_json::json!({ "variable": "size" });
+        let generator = graph_generator(
+            vec![
+                node("start", NodeType::Start, serde_json::json!({})),
+                node(
+                    "dist",
+                    NodeType::Distribution,
+                    serde_json::json!({ "kind": "uniform", "min": 12.0, "max": 20.0, "output": "size" }),
+                ),
+                node(
+                    "room",
+                    NodeType::Room,
+                    serde_json::json!({
+                        "minWidth": size, "maxWidth": size, "minHeight": size, "maxHeight": size
+                    }),
+                ),
+            ],
+            vec![edge("start", "dist"), edge("dist", "room")],
+        );
+
+        for seed in 0..10 {
+            let layout = GraphExecutor::new(seed, HashMap::new())
+                .execute(&generator)
+                .unwrap();
+            let bounds = &layout.rooms[0].bounds;
+            assert!((12.0..=20.0).contains(&bounds.width), "seed {}", seed);
+            assert_eq!(bounds.width, bounds.height);
+        }
+    }
+
+    #[test]
+    fn test_uniform_distribution_stays_in_range() {
+        let values: Vec<f64> = sample_distribution(
+            serde_json::json!({ "kind": "uniform", "min": 4.0, "max": 9.0, "output": "size" }),
+            2000,
+        )
+        .iter()
+        .map(|v| v.as_f64().unwrap())
+        .collect();
+
+        assert!(values.iter().all(|v| (4.0..=9.0).contains(v)));
+        assert!(values.iter().any(|&v| v < 4.5));
+        assert!(values.iter().any(|&v| v > 8.5));
+    }
+
+    #[test]
+    fn test_normal_distribution_matches_mean_and_stddev() {
+        let values: Vec<f64> = sample_distribution(
+            serde_json::json!({ "kind": "normal", "mean": 20.0, "stddev": 3.0, "output": "size" }),
+            5000,
+        )
+        .iter()
+        .map(|v| v.as_f64().unwrap())
+        .collect();
+
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        assert!((mean - 20.0).abs() < 0.2, "mean {}", mean);
+        assert!(
+            (variance.sqrt() - 3.0).abs() < 0.2,
+            "stddev {}",
+            variance.sqrt()
+        );
+    }
+
+    #[test]
+    fn test_weighted_distribution_follows_weights() {
+        let values = sample_distribution(
+            serde_json::json!({
+                "kind": "weighted",
+                "output": "size",
+                "choices": [
+                    { "value": "small", "weight": 1.0 },
+                    { "value": "medium", "weight": 3.0 },
+                    { "value": "large", "weight": 0.0 }
+                ]
+            }),
+            4000,
+        );
+
+        let share = |name: &str| values.iter().filter(|v| *v == name).count() as f64 / 4000.0;
+        assert!((share("small") - 0.25).abs() < 0.03);
+        assert!((share("medium") - 0.75).abs() < 0.03);
+        assert_eq!(share("large"), 0.0);
+    }
 }
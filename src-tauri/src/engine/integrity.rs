@@ -0,0 +1,91 @@
+//! Transport integrity checksums for layouts
+//!
+//! Unlike a reproducibility fingerprint (which identifies how a layout was
+//! generated), the checksum covers the exact layout content so corruption in
+//! transit between systems is detected.
+
+use crate::models::DungeonLayout;
+
+/// CRC-32 (IEEE) of the layout's canonical JSON serialization, as 8 hex digits
+pub fn layout_checksum(layout: &DungeonLayout) -> Result<String, String> {
+    // Round-trip through `Value` so map keys serialize in sorted order
+    let canonical = serde_json::to_value(layout)
+        .and_then(|v| serde_json::to_vec(&v))
+        .map_err(|e| format!("Failed to serialize layout: {}", e))?;
+
+    Ok(format!("{:08x}", crc32(&canonical)))
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GeneratedRoom, LayoutPosition, Rectangle};
+    use std::collections::HashMap;
+
+    fn sample_layout() -> DungeonLayout {
+        DungeonLayout {
+            rooms: vec![GeneratedRoom {
+                id: "room_0".to_string(),
+                room_type: "start".to_string(),
+                bounds: Rectangle {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 8.0,
+                    height: 6.0,
+                },
+                tiles: None,
+                entities: vec![],
+                metadata: HashMap::from([
+                    ("shape".to_string(), serde_json::json!("Rectangular")),
+                    ("theme".to_string(), serde_json::json!("crypt")),
+                ]),
+            }],
+            connections: vec![],
+            spawn_points: vec![],
+            player_start: LayoutPosition { x: 4.0, y: 3.0 },
+            exits: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_checksum_survives_round_trip() {
+        let layout = sample_layout();
+        let json = serde_json::to_string(&layout).unwrap();
+        let transported: DungeonLayout = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            layout_checksum(&layout).unwrap(),
+            layout_checksum(&transported).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_mutated_layout_changes_checksum() {
+        let layout = sample_layout();
+        let mut mutated = layout.clone();
+        mutated.rooms[0].bounds.width += 0.5;
+
+        assert_ne!(
+            layout_checksum(&layout).unwrap(),
+            layout_checksum(&mutated).unwrap()
+        );
+    }
+}
@@ -0,0 +1,410 @@
+//! Room connectivity graph built from a generated layout
+//!
+//! Rooms are vertices and `RoomConnection`s are undirected edges. Used by the
+//! analysis commands to answer reachability and path questions.
+
+use super::RoomGenerator;
+use crate::models::{DungeonLayout, GeneratedRoom, LayoutPosition, Rectangle};
+use std::collections::{HashMap, VecDeque};
+
+pub struct LayoutGraph<'a> {
+    layout: &'a DungeonLayout,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl<'a> LayoutGraph<'a> {
+    pub fn new(layout: &'a DungeonLayout) -> Self {
+        let index: HashMap<&str, usize> = layout
+            .rooms
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (r.id.as_str(), i))
+            .collect();
+
+        let mut adjacency = vec![Vec::new(); layout.rooms.len()];
+        for conn in &layout.connections {
+            if let (Some(&a), Some(&b)) = (
+                index.get(conn.from_room_id.as_str()),
+                index.get(conn.to_room_id.as_str()),
+            ) {
+                if a != b && !adjacency[a].contains(&b) {
+                    adjacency[a].push(b);
+                    adjacency[b].push(a);
+                }
+            }
+        }
+
+        Self { layout, adjacency }
+    }
+
+    /// Index of the room containing `player_start`, falling back to the first room
+    pub fn start_room(&self) -> Option<usize> {
+        room_containing(self.layout, &self.layout.player_start)
+            .or_else(|| (!self.layout.rooms.is_empty()).then_some(0))
+    }
+
+    /// Index of the room containing the first exit, falling back to the last room
+    pub fn exit_room(&self) -> Option<usize> {
+        self.layout
+            .exits
+            .first()
+            .and_then(|exit| room_containing(self.layout, exit))
+            .or_else(|| self.layout.rooms.len().checked_sub(1))
+    }
+
+    pub fn neighbors(&self, room: usize) -> &[usize] {
+        &self.adjacency[room]
+    }
+
+    /// Shortest room path from the start room to the exit room (inclusive)
+    pub fn critical_path(&self) -> Vec<usize> {
+        match (self.start_room(), self.exit_room()) {
+            (Some(start), Some(exit)) => self.shortest_path(start, exit).unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    /// Shortest room path between two rooms (inclusive), if they are connected
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut previous = vec![None; self.adjacency.len()];
+        let mut visited = vec![false; self.adjacency.len()];
+        let mut queue = VecDeque::new();
+        visited[from] = true;
+        queue.push_back(from);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to];
+                let mut node = to;
+                while let Some(prev) = previous[node] {
+                    path.push(prev);
+                    node = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &neighbor in &self.adjacency[current] {
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+                    previous[neighbor] = Some(current);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Breadth-first room distances from `from`; `None` marks unreachable rooms
+    pub fn distances_from(&self, from: usize) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.adjacency.len()];
+        let mut queue = VecDeque::new();
+        distances[from] = Some(0);
+        queue.push_back(from);
+
+        while let Some(current) = queue.pop_front() {
+            let next_distance = distances[current].unwrap_or(0) + 1;
+            for &neighbor in &self.adjacency[current] {
+                if distances[neighbor].is_none() {
+                    distances[neighbor] = Some(next_distance);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Rooms whose removal disconnects the graph, ascending, via DFS low-link
+    pub fn articulation_points(&self) -> Vec<usize> {
+        let count = self.adjacency.len();
+        let mut discovery = vec![None; count];
+        let mut low = vec![0; count];
+        let mut is_cut = vec![false; count];
+        let mut time = 0;
+        for root in 0..count {
+            if discovery[root].is_none() {
+                self.low_link(root, None, &mut time, &mut discovery, &mut low, &mut is_cut);
+            }
+        }
+        (0..count).filter(|&i| is_cut[i]).collect()
+    }
+
+    fn low_link(
+        &self,
+        room: usize,
+        parent: Option<usize>,
+        time: &mut usize,
+        discovery: &mut [Option<usize>],
+        low: &mut [usize],
+        is_cut: &mut [bool],
+    ) {
+        discovery[room] = Some(*time);
+        low[room] = *time;
+        *time += 1;
+        let mut children = 0;
+
+        for &neighbor in &self.adjacency[room] {
+            match discovery[neighbor] {
+                Some(seen) => {
+                    if Some(neighbor) != parent {
+                        low[room] = low[room].min(seen);
+                    }
+                }
+                None => {
+                    children += 1;
+                    self.low_link(neighbor, Some(room), time, discovery, low, is_cut);
+                    low[room] = low[room].min(low[neighbor]);
+                    // A child subtree that cannot climb above this room hangs off it alone
+                    if parent.is_some() && discovery[room].is_some_and(|d| low[neighbor] >= d) {
+                        is_cut[room] = true;
+                    }
+                }
+            }
+        }
+
+        // The DFS root is a cut room exactly when it has several subtrees
+        if parent.is_none() && children > 1 {
+            is_cut[room] = true;
+        }
+    }
+
+    /// Rooms reachable from the start room
+    pub fn reachable_from_start(&self) -> Vec<bool> {
+        match self.start_room() {
+            Some(start) => self
+                .distances_from(start)
+                .iter()
+                .map(|d| d.is_some())
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+/// Minimum spanning tree over room centers (Prim's algorithm, Euclidean weights).
+///
+/// Returns `(from_room_id, to_room_id, distance)` edges; `n` rooms yield `n - 1` edges.
+pub fn room_mst(layout: &DungeonLayout) -> Vec<(String, String, f64)> {
+    rooms_mst(&layout.rooms)
+}
+
+/// [`room_mst`] over a bare room list, for callers that have no layout yet
+pub fn rooms_mst(rooms: &[GeneratedRoom]) -> Vec<(String, String, f64)> {
+    let centers: Vec<LayoutPosition> = rooms.iter().map(RoomGenerator::get_center).collect();
+    let count = centers.len();
+    if count < 2 {
+        return vec![];
+    }
+
+    let distance = |a: usize, b: usize| {
+        ((centers[a].x - centers[b].x).powi(2) + (centers[a].y - centers[b].y).powi(2)).sqrt()
+    };
+
+    let mut in_tree = vec![false; count];
+    // Cheapest known link from the tree to each room: (distance, tree room)
+    let mut best: Vec<(f64, usize)> = (0..count).map(|i| (distance(0, i), 0)).collect();
+    in_tree[0] = true;
+
+    let mut edges = Vec::with_capacity(count - 1);
+    for _ in 1..count {
+        let next = (0..count)
+            .filter(|&i| !in_tree[i])
+            .min_by(|&a, &b| best[a].0.total_cmp(&best[b].0))
+            .expect("rooms remain outside the tree");
+        let (weight, parent) = best[next];
+        in_tree[next] = true;
+        edges.push((rooms[parent].id.clone(), rooms[next].id.clone(), weight));
+
+        for i in 0..count {
+            let d = distance(next, i);
+            if !in_tree[i] && d < best[i].0 {
+                best[i] = (d, next);
+            }
+        }
+    }
+
+    edges
+}
+
+/// Delaunay triangulation of room centers (Bowyer-Watson) as pairs of room indices.
+///
+/// Collinear centers yield the path along their line. A room sharing its
+/// center with an earlier room is connected to that room instead of being
+/// triangulated. Fewer than two rooms yield no edges. Pairs are `(low, high)`
+/// and sorted.
+pub fn room_delaunay(rooms: &[GeneratedRoom]) -> Vec<(usize, usize)> {
+    let mut points: Vec<(f64, f64)> = rooms
+        .iter()
+        .map(RoomGenerator::get_center)
+        .map(|c| (c.x, c.y))
+        .collect();
+    let count = points.len();
+    if count < 2 {
+        return vec![];
+    }
+
+    // Super triangle comfortably enclosing every center
+    let (min_x, max_x) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+        (lo.min(p.0), hi.max(p.0))
+    });
+    let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+        (lo.min(p.1), hi.max(p.1))
+    });
+    let span = (max_x - min_x).max(max_y - min_y).max(1.0);
+    let (mid_x, mid_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    points.push((mid_x - 20.0 * span, mid_y - span));
+    points.push((mid_x, mid_y + 20.0 * span));
+    points.push((mid_x + 20.0 * span, mid_y - span));
+
+    // Coincident centers can't be inserted twice, so later ones join their twin
+    let twins: Vec<Option<usize>> = (0..count)
+        .map(|i| (0..i).find(|&j| points[j] == points[i]))
+        .collect();
+
+    let mut triangles: Vec<[usize; 3]> = vec![[count, count + 1, count + 2]];
+    for p in (0..count).filter(|&p| twins[p].is_none()) {
+        let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) = triangles
+            .into_iter()
+            .partition(|t| in_circumcircle(&points, *t, points[p]));
+        triangles = good;
+
+        // Edges of the cavity: those belonging to exactly one removed triangle
+        let edges_of = |t: &[usize; 3]| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])];
+        let same = |a: (usize, usize), b: (usize, usize)| a == b || a == (b.1, b.0);
+        for (i, t) in bad.iter().enumerate() {
+            for edge in edges_of(t) {
+                let shared = bad
+                    .iter()
+                    .enumerate()
+                    .any(|(j, other)| j != i && edges_of(other).iter().any(|&e| same(e, edge)));
+                if !shared {
+                    triangles.push([edge.0, edge.1, p]);
+                }
+            }
+        }
+    }
+
+    let mut edges: Vec<(usize, usize)> = triangles
+        .iter()
+        .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+        .filter(|&(a, b)| a < count && b < count)
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .chain(
+            twins
+                .iter()
+                .enumerate()
+                .filter_map(|(i, twin)| twin.map(|j| (j, i))),
+        )
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+/// Whether `p` lies strictly inside the circle through the triangle's corners
+fn in_circumcircle(points: &[(f64, f64)], t: [usize; 3], p: (f64, f64)) -> bool {
+    let [a, b, c] = t.map(|i| (points[i].0 - p.0, points[i].1 - p.1));
+    let det = (a.0 * a.0 + a.1 * a.1) * (b.0 * c.1 - c.0 * b.1)
+        - (b.0 * b.0 + b.1 * b.1) * (a.0 * c.1 - c.0 * a.1)
+        + (c.0 * c.0 + c.1 * c.1) * (a.0 * b.1 - b.0 * a.1);
+    // The determinant's sign flips with the triangle's winding
+    let orientation = (points[t[1]].0 - points[t[0]].0) * (points[t[2]].1 - points[t[0]].1)
+        - (points[t[1]].1 - points[t[0]].1) * (points[t[2]].0 - points[t[0]].0);
+    det * orientation.signum() > 1e-9
+}
+
+/// Find the room whose bounds contain a position
+pub fn room_containing(layout: &DungeonLayout, position: &LayoutPosition) -> Option<usize> {
+    layout
+        .rooms
+        .iter()
+        .position(|r| contains(&r.bounds, position))
+}
+
+fn contains(bounds: &Rectangle, position: &LayoutPosition) -> bool {
+    position.x >= bounds.x
+        && position.x <= bounds.x + bounds.width
+        && position.y >= bounds.y
+        && position.y <= bounds.y + bounds.height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room_at(id: &str, x: f64, y: f64) -> GeneratedRoom {
+        GeneratedRoom {
+            id: id.to_string(),
+            room_type: "default".to_string(),
+            bounds: Rectangle {
+                x,
+                y,
+                width: 2.0,
+                height: 2.0,
+            },
+            tiles: None,
+            entities: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_room_mst_of_four_rooms() {
+        // Three corners of a square plus a far room: the MST takes two square
+        // sides (10 each) and the 30-unit hop from b to d
+        let layout = DungeonLayout {
+            rooms: vec![
+                room_at("a", 0.0, 0.0),
+                room_at("b", 10.0, 0.0),
+                room_at("c", 0.0, 10.0),
+                room_at("d", 40.0, 0.0),
+            ],
+            connections: vec![],
+            spawn_points: vec![],
+            player_start: LayoutPosition { x: 1.0, y: 1.0 },
+            exits: vec![],
+            metadata: HashMap::new(),
+        };
+
+        let mst = room_mst(&layout);
+        let total: f64 = mst.iter().map(|(_, _, d)| d).sum();
+
+        assert_eq!(mst.len(), 3);
+        assert!((total - 50.0).abs() < 1e-9);
+        assert!(mst
+            .iter()
+            .any(|(a, b, _)| (a == "b" && b == "d") || (a == "d" && b == "b")));
+    }
+
+    #[test]
+    fn test_room_delaunay_of_square_and_line() {
+        let square = vec![
+            room_at("a", 0.0, 0.0),
+            room_at("b", 10.0, 0.0),
+            room_at("c", 0.0, 10.0),
+            room_at("d", 11.0, 11.0),
+        ];
+        // Four sides plus the one diagonal that avoids the cocircular tie
+        let edges = room_delaunay(&square);
+        assert_eq!(edges.len(), 5);
+        assert!(edges.contains(&(1, 2)));
+
+        let line: Vec<GeneratedRoom> = (0..4)
+            .map(|i| room_at(&i.to_string(), i as f64 * 10.0, 0.0))
+            .collect();
+        assert_eq!(room_delaunay(&line), vec![(0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn test_room_delaunay_connects_coincident_centers() {
+        let rooms = vec![
+            room_at("a", 0.0, 0.0),
+            room_at("b", 10.0, 0.0),
+            room_at("c", 0.0, 10.0),
+            room_at("d", 0.0, 0.0),
+        ];
+
+        assert_eq!(room_delaunay(&rooms), vec![(0, 1), (0, 2), (0, 3), (1, 2)]);
+    }
+}
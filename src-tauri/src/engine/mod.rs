@@ -1,7 +1,20 @@
 //! Dungeon generation engine that interprets node graphs
 
+pub mod analysis;
+pub mod constraints;
+pub mod editing;
+pub mod export;
 mod graph_executor;
+pub mod integrity;
+mod layout_graph;
+mod postprocess;
+mod regenerate;
 mod room_generator;
+mod sampling;
+pub mod stats;
+pub mod validation;
+mod weights;
 
-pub use graph_executor::GraphExecutor;
-pub use room_generator::RoomGenerator;
+pub use graph_executor::{ExecutionContext, GraphExecutor};
+pub use regenerate::regenerate_with_locks;
+pub use room_generator::{rectangles_overlap, Direction, RoomConfigJson, RoomGenerator};
@@ -0,0 +1,360 @@
+//! Post-generation passes applied to a finished layout
+//!
+//! Each pass is opt-in via a generation parameter and runs after graph
+//! execution, so it sees the complete set of rooms and connections.
+
+use super::layout_graph::{self, LayoutGraph};
+use super::RoomGenerator;
+use crate::models::{DungeonLayout, LayoutPosition, Rectangle, SpawnPoint};
+use rand_chacha::ChaCha8Rng;
+
+/// Upper bound on separation sweeps so dense layouts can't loop forever
+const MAX_SEPARATION_PASSES: usize = 50;
+
+/// Slack when comparing corridor lengths, so float error doesn't trigger another push
+const LENGTH_EPSILON: f64 = 1e-9;
+
+/// Most decimal places `round_coordinates` keeps; larger factors overflow to infinity
+const MAX_COORD_PRECISION: u64 = 15;
+
+/// Push rooms apart until every pair is at least `min_gap` units apart.
+///
+/// The later room of a conflicting pair is moved along the axis on which the
+/// two rooms are already furthest apart (their axis of closest approach), and
+/// its doors, entities and spawn points move with it.
+pub fn enforce_min_room_gap(layout: &mut DungeonLayout, min_gap: f64) {
+    if min_gap <= 0.0 {
+        return;
+    }
+
+    for _ in 0..MAX_SEPARATION_PASSES {
+        let mut moved = false;
+
+        for i in 0..layout.rooms.len() {
+            for j in (i + 1)..layout.rooms.len() {
+                let a = &layout.rooms[i].bounds;
+                let b = &layout.rooms[j].bounds;
+                let (gap_x, gap_y) = axis_gaps(a, b);
+                if gap_x.max(gap_y) >= min_gap {
+                    continue;
+                }
+
+                let (dx, dy) = if gap_x >= gap_y {
+                    let push = min_gap - gap_x;
+                    let sign = if center_x(b) >= center_x(a) {
+                        1.0
+                    } else {
+                        -1.0
+                    };
+                    (push * sign, 0.0)
+                } else {
+                    let push = min_gap - gap_y;
+                    let sign = if center_y(b) >= center_y(a) {
+                        1.0
+                    } else {
+                        -1.0
+                    };
+                    (0.0, push * sign)
+                };
+
+                translate_room(layout, j, dx, dy);
+                moved = true;
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Lengthen corridors shorter than `min_length` by pushing rooms apart.
+///
+/// The `to` room of a short connection moves directly away from the `from`
+/// door (or away from the `from` room's center when the doors coincide) until
+/// the doors are `min_length` apart. Its doors, entities and spawn points move
+/// with it, so its other corridors are rechecked on the next sweep.
+pub fn enforce_min_corridor_length(layout: &mut DungeonLayout, min_length: f64) {
+    if min_length <= 0.0 {
+        return;
+    }
+
+    for _ in 0..MAX_SEPARATION_PASSES {
+        let mut moved = false;
+
+        for c in 0..layout.connections.len() {
+            let conn = &layout.connections[c];
+            let (from, to) = (&conn.from_door, &conn.to_door);
+            let length = ((to.x - from.x).powi(2) + (to.y - from.y).powi(2)).sqrt();
+            if length >= min_length - LENGTH_EPSILON {
+                continue;
+            }
+            let index_of = |id: &str| layout.rooms.iter().position(|r| r.id == id);
+            let (Some(from_room), Some(to_room)) =
+                (index_of(&conn.from_room_id), index_of(&conn.to_room_id))
+            else {
+                continue;
+            };
+            if from_room == to_room {
+                continue;
+            }
+
+            let (ux, uy) = if length > LENGTH_EPSILON {
+                ((to.x - from.x) / length, (to.y - from.y) / length)
+            } else {
+                let a = RoomGenerator::get_center(&layout.rooms[from_room]);
+                let b = RoomGenerator::get_center(&layout.rooms[to_room]);
+                let d = ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt();
+                if d > LENGTH_EPSILON {
+                    ((b.x - a.x) / d, (b.y - a.y) / d)
+                } else {
+                    (1.0, 0.0)
+                }
+            };
+            let push = min_length - length;
+            translate_room(layout, to_room, ux * push, uy * push);
+            moved = true;
+        }
+
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Top up loot on the critical path until its rooms hold at least `min_loot` loot entities.
+///
+/// Each missing item goes to the path room with the least loot so far, earlier
+/// rooms first on ties. Layouts without a start-to-exit path are left alone.
+pub fn ensure_critical_loot(layout: &mut DungeonLayout, rng: &mut ChaCha8Rng, min_loot: usize) {
+    let path = LayoutGraph::new(layout).critical_path();
+    let loot_in = |layout: &DungeonLayout, index: usize| {
+        layout.rooms[index]
+            .entities
+            .iter()
+            .filter(|e| e.entity_type == "loot")
+            .count()
+    };
+
+    let mut total: usize = path.iter().map(|&i| loot_in(layout, i)).sum();
+    while total < min_loot {
+        let Some(&poorest) = path.iter().min_by_key(|&&i| loot_in(layout, i)) else {
+            return;
+        };
+        RoomGenerator::add_entities(rng, &mut layout.rooms[poorest], "loot", 1, 1, 0.0);
+        total += 1;
+    }
+}
+
+/// Mirror the player start and exits into `spawn_points` as "player_start" and "exit" spawns.
+///
+/// The dedicated fields are kept. Markers outside every room get an empty `room_id`.
+pub fn add_marker_spawns(layout: &mut DungeonLayout) {
+    let markers: Vec<(&str, LayoutPosition)> =
+        std::iter::once(("player_start", layout.player_start.clone()))
+            .chain(layout.exits.iter().map(|exit| ("exit", exit.clone())))
+            .collect();
+
+    for (spawn_type, position) in markers {
+        let room_id = layout_graph::room_containing(layout, &position)
+            .map(|i| layout.rooms[i].id.clone())
+            .unwrap_or_default();
+        layout.spawn_points.push(SpawnPoint {
+            id: format!("spawn_{}", layout.spawn_points.len()),
+            spawn_type: spawn_type.to_string(),
+            position,
+            room_id,
+        });
+    }
+}
+
+/// Round every output coordinate (rooms, doors, spawns, entities, markers) to `decimals` places.
+///
+/// `decimals` is capped at `MAX_COORD_PRECISION`; beyond that f64 has no digits left to round.
+pub fn round_coordinates(layout: &mut DungeonLayout, decimals: u64) {
+    let factor = 10f64.powi(decimals.min(MAX_COORD_PRECISION) as i32);
+    let round = |v: &mut f64| *v = (*v * factor).round() / factor;
+    let round_position = |p: &mut LayoutPosition| {
+        round(&mut p.x);
+        round(&mut p.y);
+    };
+
+    for room in &mut layout.rooms {
+        round(&mut room.bounds.x);
+        round(&mut room.bounds.y);
+        round(&mut room.bounds.width);
+        round(&mut room.bounds.height);
+        for entity in &mut room.entities {
+            round_position(&mut entity.position);
+        }
+    }
+    for conn in &mut layout.connections {
+        round_position(&mut conn.from_door);
+        round_position(&mut conn.to_door);
+    }
+    for spawn in &mut layout.spawn_points {
+        round_position(&mut spawn.position);
+    }
+    round_position(&mut layout.player_start);
+    for exit in &mut layout.exits {
+        round_position(exit);
+    }
+}
+
+/// Move a room and everything attached to it (entities, spawns, doors)
+pub fn translate_room(layout: &mut DungeonLayout, index: usize, dx: f64, dy: f64) {
+    let room = &mut layout.rooms[index];
+    RoomGenerator::translate(room, dx, dy);
+
+    let room_id = room.id.clone();
+    for spawn in layout
+        .spawn_points
+        .iter_mut()
+        .filter(|s| s.room_id == room_id)
+    {
+        spawn.position.x += dx;
+        spawn.position.y += dy;
+    }
+
+    for conn in &mut layout.connections {
+        if conn.from_room_id == room_id {
+            conn.from_door.x += dx;
+            conn.from_door.y += dy;
+        }
+        if conn.to_room_id == room_id {
+            conn.to_door.x += dx;
+            conn.to_door.y += dy;
+        }
+    }
+}
+
+/// Empty space between two rectangles along each axis (negative when they overlap on that axis)
+fn axis_gaps(a: &Rectangle, b: &Rectangle) -> (f64, f64) {
+    let gap_x = (b.x - (a.x + a.width)).max(a.x - (b.x + b.width));
+    let gap_y = (b.y - (a.y + a.height)).max(a.y - (b.y + b.height));
+    (gap_x, gap_y)
+}
+
+fn center_x(r: &Rectangle) -> f64 {
+    r.x + r.width / 2.0
+}
+
+fn center_y(r: &Rectangle) -> f64 {
+    r.y + r.height / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GeneratedRoom, LayoutPosition, RoomConnection};
+    use std::collections::HashMap;
+
+    fn room(id: &str, x: f64, y: f64, width: f64, height: f64) -> GeneratedRoom {
+        GeneratedRoom {
+            id: id.to_string(),
+            room_type: "default".to_string(),
+            bounds: Rectangle {
+                x,
+                y,
+                width,
+                height,
+            },
+            tiles: None,
+            entities: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_min_room_gap_separates_touching_rooms() {
+        let mut layout = DungeonLayout {
+            rooms: vec![
+                room("a", 0.0, 0.0, 10.0, 10.0),
+                room("b", 10.5, 2.0, 10.0, 10.0),
+            ],
+            connections: vec![RoomConnection {
+                from_room_id: "a".to_string(),
+                to_room_id: "b".to_string(),
+                from_door: LayoutPosition { x: 10.0, y: 5.0 },
+                to_door: LayoutPosition { x: 10.5, y: 5.0 },
+            }],
+            spawn_points: vec![],
+            player_start: LayoutPosition { x: 5.0, y: 5.0 },
+            exits: vec![],
+            metadata: HashMap::new(),
+        };
+
+        enforce_min_room_gap(&mut layout, 4.0);
+
+        let (gap_x, gap_y) = axis_gaps(&layout.rooms[0].bounds, &layout.rooms[1].bounds);
+        assert!(gap_x.max(gap_y) >= 4.0 - 1e-9);
+        // The door moved with its room and stays on the room's left edge
+        assert!((layout.connections[0].to_door.x - layout.rooms[1].bounds.x).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_critical_loot_tops_up_path_rooms() {
+        use rand::SeedableRng;
+
+        // a - b - c is the critical path; d hangs off b and holds all the loot
+        let mut rooms = vec![
+            room("a", 0.0, 0.0, 10.0, 10.0),
+            room("b", 20.0, 0.0, 10.0, 10.0),
+            room("c", 40.0, 0.0, 10.0, 10.0),
+            room("d", 20.0, 20.0, 10.0, 10.0),
+        ];
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        RoomGenerator::add_entities(&mut rng, &mut rooms[3], "loot", 4, 4, 0.0);
+        RoomGenerator::add_entities(&mut rng, &mut rooms[1], "loot", 1, 1, 0.0);
+        let link = |from: &str, to: &str| RoomConnection {
+            from_room_id: from.to_string(),
+            to_room_id: to.to_string(),
+            from_door: LayoutPosition { x: 0.0, y: 0.0 },
+            to_door: LayoutPosition { x: 0.0, y: 0.0 },
+        };
+        let mut layout = DungeonLayout {
+            rooms,
+            connections: vec![link("a", "b"), link("b", "c"), link("b", "d")],
+            spawn_points: vec![],
+            player_start: LayoutPosition { x: 5.0, y: 5.0 },
+            exits: vec![LayoutPosition { x: 45.0, y: 5.0 }],
+            metadata: HashMap::new(),
+        };
+
+        ensure_critical_loot(&mut layout, &mut rng, 5);
+
+        let loot = |i: usize| {
+            layout.rooms[i]
+                .entities
+                .iter()
+                .filter(|e| e.entity_type == "loot")
+                .count()
+        };
+        assert_eq!((loot(0), loot(1), loot(2), loot(3)), (2, 2, 1, 4));
+        // Topped-up items continue the room's numbering instead of reusing ids
+        let ids: Vec<&str> = layout.rooms[1]
+            .entities
+            .iter()
+            .map(|e| e.id.as_str())
+            .collect();
+        assert_eq!(ids, ["b_loot_entity_0", "b_loot_entity_1"]);
+    }
+
+    #[test]
+    fn test_round_coordinates_caps_huge_precision() {
+        let mut layout = DungeonLayout {
+            rooms: vec![room("a", 1.25, 2.5, 10.125, 10.0)],
+            connections: vec![],
+            spawn_points: vec![],
+            player_start: LayoutPosition { x: 5.0, y: 5.0 },
+            exits: vec![],
+            metadata: HashMap::new(),
+        };
+
+        round_coordinates(&mut layout, u64::MAX);
+
+        let bounds = &layout.rooms[0].bounds;
+        assert_eq!((bounds.x, bounds.y, bounds.width), (1.25, 2.5, 10.125));
+        assert_eq!(layout.player_start.x, 5.0);
+    }
+}
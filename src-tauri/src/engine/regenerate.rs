@@ -0,0 +1,161 @@
+//! Regeneration that preserves hand-picked rooms from a previous result
+
+use super::graph_executor::{ExecutionError, GraphExecutor};
+use super::RoomGenerator;
+use crate::models::generator::Generator;
+use crate::models::{DungeonLayout, LayoutPosition, RoomConnection};
+use std::collections::HashMap;
+
+/// Re-run a generator with a new seed while keeping `locked_room_ids` exactly as in `prior`.
+///
+/// Locked rooms replace the regenerated room with the same id (or are appended
+/// when the new layout has no such room), keep their entities and spawn points,
+/// and every corridor touching them is re-attached to one of their existing doors.
+pub fn regenerate_with_locks(
+    generator: &Generator,
+    prior: &DungeonLayout,
+    locked_room_ids: &[String],
+    seed: u64,
+    parameters: HashMap<String, serde_json::Value>,
+) -> Result<(DungeonLayout, u32), ExecutionError> {
+    let mut executor = GraphExecutor::new(seed, parameters);
+    let mut layout = executor.execute(generator)?;
+
+    for locked in prior
+        .rooms
+        .iter()
+        .filter(|r| locked_room_ids.contains(&r.id))
+    {
+        match layout.rooms.iter().position(|r| r.id == locked.id) {
+            Some(index) => layout.rooms[index] = locked.clone(),
+            None => layout.rooms.push(locked.clone()),
+        }
+
+        layout.spawn_points.retain(|s| s.room_id != locked.id);
+        layout.spawn_points.extend(
+            prior
+                .spawn_points
+                .iter()
+                .filter(|s| s.room_id == locked.id)
+                .cloned(),
+        );
+
+        let prior_doors: Vec<LayoutPosition> = prior
+            .connections
+            .iter()
+            .filter_map(|c| {
+                if c.from_room_id == locked.id {
+                    Some(c.from_door.clone())
+                } else if c.to_room_id == locked.id {
+                    Some(c.to_door.clone())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let fallback_door = RoomGenerator::get_center(locked);
+        let nearest_door = |target: &LayoutPosition| {
+            prior_doors
+                .iter()
+                .min_by(|a, b| distance(a, target).total_cmp(&distance(b, target)))
+                .cloned()
+                .unwrap_or_else(|| fallback_door.clone())
+        };
+
+        let mut connected = false;
+        for conn in &mut layout.connections {
+            if conn.from_room_id == locked.id {
+                conn.from_door = nearest_door(&conn.to_door);
+                connected = true;
+            }
+            if conn.to_room_id == locked.id {
+                conn.to_door = nearest_door(&conn.from_door);
+                connected = true;
+            }
+        }
+
+        // An appended room links to the closest regenerated room
+        if !connected {
+            let center = RoomGenerator::get_center(locked);
+            let neighbor = layout
+                .rooms
+                .iter()
+                .filter(|r| !locked_room_ids.contains(&r.id))
+                .min_by(|a, b| {
+                    distance(&RoomGenerator::get_center(a), &center)
+                        .total_cmp(&distance(&RoomGenerator::get_center(b), &center))
+                });
+            if let Some(neighbor) = neighbor {
+                let neighbor_center = RoomGenerator::get_center(neighbor);
+                layout.connections.push(RoomConnection {
+                    from_room_id: neighbor.id.clone(),
+                    to_room_id: locked.id.clone(),
+                    from_door: neighbor_center.clone(),
+                    to_door: nearest_door(&neighbor_center),
+                });
+            }
+        }
+    }
+
+    Ok((layout, executor.node_executions()))
+}
+
+fn distance(a: &LayoutPosition, b: &LayoutPosition) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_room_generator() -> Generator {
+        serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Three rooms",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } },
+                    { "id": "chain", "type": "room_chain", "position": { "x": 100.0, "y": 0.0 },
+                      "data": { "label": "Chain", "count": 3 } },
+                    { "id": "output", "type": "output", "position": { "x": 200.0, "y": 0.0 },
+                      "data": { "label": "Output" } }
+                ],
+                "edges": [
+                    { "id": "e1", "source": { "nodeId": "start", "portId": "out" },
+                      "target": { "nodeId": "chain", "portId": "in" } },
+                    { "id": "e2", "source": { "nodeId": "chain", "portId": "out" },
+                      "target": { "nodeId": "output", "portId": "in" } }
+                ]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_locked_rooms_survive_regeneration() {
+        let generator = three_room_generator();
+        let prior = GraphExecutor::new(1, HashMap::new())
+            .execute(&generator)
+            .unwrap();
+        let locked_id = prior.rooms[2].id.clone();
+        let locked = vec![locked_id.clone()];
+
+        let (regenerated, _) =
+            regenerate_with_locks(&generator, &prior, &locked, 2, HashMap::new()).unwrap();
+
+        let find = |layout: &DungeonLayout, id: &str| {
+            serde_json::to_value(layout.rooms.iter().find(|r| r.id == id).unwrap()).unwrap()
+        };
+        assert_eq!(find(&prior, &locked_id), find(&regenerated, &locked_id));
+        assert_ne!(
+            find(&prior, &prior.rooms[1].id),
+            find(&regenerated, &prior.rooms[1].id)
+        );
+        assert!(regenerated
+            .connections
+            .iter()
+            .any(|c| c.to_room_id == locked_id));
+    }
+}
@@ -1,8 +1,11 @@
 //! Room generation utilities
 
+use super::sampling::{standard_normal, uniform, uniform_or};
+use super::weights::{pick_weighted, scale_weights};
 use crate::models::{GeneratedRoom, LayoutPosition, PlacedEntity, Rectangle};
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Configuration for generating a room
@@ -15,6 +18,11 @@ pub struct RoomConfig {
     pub shape: RoomShape,
     pub room_type: String,
     pub tags: Vec<String>,
+    pub size_distribution: SizeDistribution,
+    /// Fill `GeneratedRoom.tiles` with a floor/wall grid
+    pub emit_tiles: bool,
+    /// Weights of the floor tile variants; floor tiles of variant `i` hold `1 + i`
+    pub floor_variants: Vec<f64>,
 }
 
 impl Default for RoomConfig {
@@ -27,6 +35,83 @@ impl Default for RoomConfig {
             shape: RoomShape::Rectangular,
             room_type: "default".to_string(),
             tags: vec![],
+            size_distribution: SizeDistribution::Uniform,
+            emit_tiles: false,
+            floor_variants: vec![],
+        }
+    }
+}
+
+/// Room configuration as sent by the editor; unset fields keep `RoomConfig` defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoomConfigJson {
+    #[serde(rename = "minWidth")]
+    pub min_width: Option<f64>,
+    #[serde(rename = "maxWidth")]
+    pub max_width: Option<f64>,
+    #[serde(rename = "minHeight")]
+    pub min_height: Option<f64>,
+    #[serde(rename = "maxHeight")]
+    pub max_height: Option<f64>,
+    pub shape: Option<String>,
+    #[serde(rename = "roomType")]
+    pub room_type: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(rename = "sizeDistribution")]
+    pub size_distribution: Option<String>,
+    #[serde(rename = "emitTiles")]
+    pub emit_tiles: Option<bool>,
+    #[serde(rename = "floorVariants")]
+    pub floor_variants: Vec<f64>,
+}
+
+impl RoomConfig {
+    /// Reject size ranges that can't be sampled: non-finite bounds or spans, or min above max
+    pub fn validate(&self) -> Result<(), String> {
+        for (axis, min, max) in [
+            ("width", self.min_width, self.max_width),
+            ("height", self.min_height, self.max_height),
+        ] {
+            if !min.is_finite() || !max.is_finite() {
+                return Err(format!("Room {} bounds must be finite", axis));
+            }
+            if !(max - min).is_finite() {
+                return Err(format!("Room {} range is too large", axis));
+            }
+            if min > max {
+                return Err(format!(
+                    "Room min {} {} is greater than max {} {}",
+                    axis, min, axis, max
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<RoomConfigJson> for RoomConfig {
+    fn from(json: RoomConfigJson) -> Self {
+        let defaults = RoomConfig::default();
+        Self {
+            min_width: json.min_width.unwrap_or(defaults.min_width),
+            max_width: json.max_width.unwrap_or(defaults.max_width),
+            min_height: json.min_height.unwrap_or(defaults.min_height),
+            max_height: json.max_height.unwrap_or(defaults.max_height),
+            shape: json
+                .shape
+                .as_deref()
+                .map(RoomShape::from)
+                .unwrap_or(defaults.shape),
+            room_type: json.room_type.unwrap_or(defaults.room_type),
+            tags: json.tags,
+            size_distribution: json
+                .size_distribution
+                .as_deref()
+                .map(SizeDistribution::from)
+                .unwrap_or(defaults.size_distribution),
+            emit_tiles: json.emit_tiles.unwrap_or(defaults.emit_tiles),
+            floor_variants: json.floor_variants,
         }
     }
 }
@@ -37,6 +122,8 @@ pub enum RoomShape {
     LShaped,
     Circular,
     Irregular,
+    /// Square boss arena with an octagonal footprint and a door centered on each side
+    Arena,
 }
 
 impl From<&str> for RoomShape {
@@ -45,11 +132,93 @@ impl From<&str> for RoomShape {
             "l-shaped" | "lshaped" => RoomShape::LShaped,
             "circular" | "circle" => RoomShape::Circular,
             "irregular" => RoomShape::Irregular,
+            "arena" => RoomShape::Arena,
             _ => RoomShape::Rectangular,
         }
     }
 }
 
+/// How room dimensions are drawn between their configured min and max
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeDistribution {
+    Uniform,
+    /// Bell curve centered on the midpoint, with the range spanning six standard deviations
+    Normal,
+    /// Triangle peaking at the midpoint
+    Triangular,
+}
+
+impl From<&str> for SizeDistribution {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "normal" | "gaussian" => SizeDistribution::Normal,
+            "triangular" | "triangle" => SizeDistribution::Triangular,
+            _ => SizeDistribution::Uniform,
+        }
+    }
+}
+
+impl SizeDistribution {
+    /// Draw a value in `min..=max`; a uniform draw over a range that can't be sampled yields `min`
+    pub fn sample(&self, rng: &mut ChaCha8Rng, min: f64, max: f64) -> f64 {
+        match self {
+            SizeDistribution::Uniform => uniform(rng, min, max).unwrap_or(min),
+            SizeDistribution::Normal => {
+                let z = standard_normal(rng);
+                let mid = (min + max) / 2.0;
+                (mid + z * (max - min) / 6.0).clamp(min, max)
+            }
+            SizeDistribution::Triangular => {
+                // Symmetric triangle as the mean of two uniform draws
+                let u = (rng.gen::<f64>() + rng.gen::<f64>()) / 2.0;
+                min + u * (max - min)
+            }
+        }
+    }
+}
+
+/// Where decorative props go inside a room
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PropPlacement {
+    /// Just inside a random wall, like torches and barrels
+    Wall,
+    /// Around the room center
+    Center,
+    /// Anywhere in the interior, like enemies
+    Random,
+}
+
+impl From<&str> for PropPlacement {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "center" | "centre" => PropPlacement::Center,
+            "random" => PropPlacement::Random,
+            _ => PropPlacement::Wall,
+        }
+    }
+}
+
+/// Distance from the wall for wall-placed props
+const PROP_WALL_INSET: f64 = 0.5;
+
+/// Smallest side of an arena, so boss rooms come out larger than the 5..10 default
+const ARENA_MIN_SIZE: f64 = 16.0;
+
+/// Fraction of an arena's side cut off each corner of its octagonal footprint
+const ARENA_CORNER_CUT: f64 = 0.25;
+
+/// Range of the fraction of each side an L-shaped room's notch takes up
+const NOTCH_FRACTION: std::ops::Range<f64> = 0.3..0.6;
+
+/// Corners an L-shaped room's notch can be cut from, in clockwise order
+const NOTCH_CORNERS: [&str; 4] = ["top_left", "top_right", "bottom_right", "bottom_left"];
+
+/// Vertices on an irregular room's boundary
+const IRREGULAR_VERTICES: usize = 12;
+
+/// Range each irregular vertex is scaled by, relative to the inscribed ellipse
+const IRREGULAR_JITTER: std::ops::Range<f64> = 0.7..1.0;
+
 pub struct RoomGenerator;
 
 impl RoomGenerator {
@@ -60,8 +229,27 @@ impl RoomGenerator {
         base_position: LayoutPosition,
         room_id: &str,
     ) -> GeneratedRoom {
-        let width = rng.gen_range(config.min_width..=config.max_width);
-        let height = rng.gen_range(config.min_height..=config.max_height);
+        let distribution = config.size_distribution;
+        let (width, height) = match config.shape {
+            RoomShape::Arena => {
+                // Arenas are square so their footprint is symmetric on both axes
+                let min = config.min_width.max(config.min_height).max(ARENA_MIN_SIZE);
+                let max = config.max_width.max(config.max_height).max(min);
+                let side = distribution.sample(rng, min, max);
+                (side, side)
+            }
+            RoomShape::Circular => {
+                // Same draws as a rectangle, trimmed to the square around the circle
+                let width = distribution.sample(rng, config.min_width, config.max_width);
+                let height = distribution.sample(rng, config.min_height, config.max_height);
+                let side = width.min(height);
+                (side, side)
+            }
+            _ => (
+                distribution.sample(rng, config.min_width, config.max_width),
+                distribution.sample(rng, config.min_height, config.max_height),
+            ),
+        };
 
         let bounds = Rectangle {
             x: base_position.x,
@@ -75,6 +263,41 @@ impl RoomGenerator {
             "shape".to_string(),
             serde_json::Value::String(format!("{:?}", config.shape)),
         );
+        // Shapes other than rectangles describe their real outline inside `bounds`
+        match config.shape {
+            RoomShape::Rectangular => {}
+            RoomShape::Arena => {
+                metadata.insert("footprint".to_string(), arena_footprint(&bounds));
+            }
+            RoomShape::LShaped => {
+                let corner = rng.gen_range(0..NOTCH_CORNERS.len());
+                let notch_width = bounds.width * rng.gen_range(NOTCH_FRACTION);
+                let notch_height = bounds.height * rng.gen_range(NOTCH_FRACTION);
+                metadata.insert(
+                    "notch".to_string(),
+                    serde_json::json!({
+                        "corner": NOTCH_CORNERS[corner],
+                        "width": notch_width,
+                        "height": notch_height,
+                    }),
+                );
+                metadata.insert(
+                    "footprint".to_string(),
+                    l_shaped_footprint(&bounds, corner, notch_width, notch_height),
+                );
+            }
+            RoomShape::Circular => {
+                let center = serde_json::json!({
+                    "x": bounds.x + bounds.width / 2.0,
+                    "y": bounds.y + bounds.height / 2.0,
+                });
+                metadata.insert("center".to_string(), center);
+                metadata.insert("radius".to_string(), serde_json::json!(bounds.width / 2.0));
+            }
+            RoomShape::Irregular => {
+                metadata.insert("footprint".to_string(), irregular_footprint(rng, &bounds));
+            }
+        }
         if !config.tags.is_empty() {
             metadata.insert(
                 "tags".to_string(),
@@ -88,14 +311,84 @@ impl RoomGenerator {
             );
         }
 
-        GeneratedRoom {
+        let mut room = GeneratedRoom {
             id: room_id.to_string(),
             room_type: config.room_type.clone(),
             bounds,
             tiles: None,
             entities: vec![],
             metadata,
+        };
+        if config.emit_tiles {
+            let mut tiles = Self::generate_tiles(&room, config.shape);
+            Self::assign_floor_variants(rng, &mut tiles, &config.floor_variants);
+            room.tiles = Some(tiles);
         }
+        room
+    }
+
+    /// Replace each floor tile with `1 + i`, drawing variant `i` by weight.
+    ///
+    /// Walls stay `0`. Draws nothing when no variant has a positive weight.
+    pub fn assign_floor_variants(rng: &mut ChaCha8Rng, tiles: &mut [Vec<i32>], weights: &[f64]) {
+        let weights = scale_weights(weights.iter().copied());
+
+        for tile in tiles.iter_mut().flatten().filter(|t| **t == 1) {
+            let Some(index) = pick_weighted(rng, &weights) else {
+                return;
+            };
+            *tile = 1 + index as i32;
+        }
+    }
+
+    /// Rasterize a room into rows of `1` (floor) and `0` (wall or outside).
+    ///
+    /// The grid has one cell per unit of the rounded bounds. Cells inside the
+    /// shape that touch its edge form the wall ring. Rooms with a `footprint`
+    /// keep the cells whose centers fall inside it. Without one, L-shaped rooms
+    /// leave out their top-right quadrant and circular rooms keep the inscribed
+    /// ellipse; other shapes fill the whole rectangle.
+    pub fn generate_tiles(room: &GeneratedRoom, shape: RoomShape) -> Vec<Vec<i32>> {
+        let width = room.bounds.width.round().max(0.0) as usize;
+        let height = room.bounds.height.round().max(0.0) as usize;
+        let (half_w, half_h) = (width as f64 / 2.0, height as f64 / 2.0);
+        let footprint = footprint_vertices(room);
+
+        let inside = |row: isize, col: isize| {
+            if row < 0 || col < 0 || row as usize >= height || col as usize >= width {
+                return false;
+            }
+            let (row, col) = (row as usize, col as usize);
+            if let Some(polygon) = &footprint {
+                let x = room.bounds.x + col as f64 + 0.5;
+                let y = room.bounds.y + row as f64 + 0.5;
+                return point_in_polygon(x, y, polygon);
+            }
+            match shape {
+                RoomShape::LShaped => !(col >= width / 2 && row < height / 2),
+                RoomShape::Circular => {
+                    let dx = (col as f64 + 0.5 - half_w) / half_w;
+                    let dy = (row as f64 + 0.5 - half_h) / half_h;
+                    dx * dx + dy * dy <= 1.0
+                }
+                _ => true,
+            }
+        };
+
+        (0..height as isize)
+            .map(|row| {
+                (0..width as isize)
+                    .map(|col| {
+                        let floor = inside(row, col)
+                            && inside(row - 1, col)
+                            && inside(row + 1, col)
+                            && inside(row, col - 1)
+                            && inside(row, col + 1);
+                        i32::from(floor)
+                    })
+                    .collect()
+            })
+            .collect()
     }
 
     /// Generate a chain of connected rooms
@@ -136,24 +429,140 @@ impl RoomGenerator {
     }
 
     /// Add entities to a room (enemies, items, etc.)
+    ///
+    /// `clustering` runs from 0 (independent uniform placement) to 1 (tight
+    /// groups): above 0, entities gather around one or two random group centers
+    /// whose spread shrinks as clustering grows.
     pub fn add_entities(
         rng: &mut ChaCha8Rng,
         room: &mut GeneratedRoom,
         entity_type: &str,
         min_count: usize,
         max_count: usize,
+        clustering: f64,
     ) {
         let count = rng.gen_range(min_count..=max_count);
         let padding = 1.5; // Keep entities away from walls
+        let center = Self::get_center(room);
+        // Rooms narrower than the padding collapse to their center line
+        let (min_x, max_x) = (
+            (room.bounds.x + padding).min(center.x),
+            (room.bounds.x + room.bounds.width - padding).max(center.x),
+        );
+        let (min_y, max_y) = (
+            (room.bounds.y + padding).min(center.y),
+            (room.bounds.y + room.bounds.height - padding).max(center.y),
+        );
+        let clustering = clustering.clamp(0.0, 1.0);
+        // Continue numbering after entities of this type already in the room
+        let first_index = room
+            .entities
+            .iter()
+            .filter(|e| e.entity_type == entity_type)
+            .count();
+
+        let mut groups = Vec::new();
+        if clustering > 0.0 && count > 0 {
+            let group_count = if count > 3 && rng.gen_bool(0.5) { 2 } else { 1 };
+            for _ in 0..group_count {
+                groups.push((
+                    uniform_or(rng, min_x, max_x, center.x),
+                    uniform_or(rng, min_y, max_y, center.y),
+                ));
+            }
+        }
+        let spread = ((max_x - min_x).min(max_y - min_y) / 2.0 * (1.0 - clustering)).max(0.5);
 
         for i in 0..count {
-            let x = room.bounds.x + rng.gen_range(padding..(room.bounds.width - padding));
-            let y = room.bounds.y + rng.gen_range(padding..(room.bounds.height - padding));
+            let (x, y) = match groups.get(i % groups.len().max(1)) {
+                Some(&(cx, cy)) => {
+                    let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+                    let distance = rng.gen_range(0.0..spread);
+                    (
+                        (cx + angle.cos() * distance).clamp(min_x, max_x),
+                        (cy + angle.sin() * distance).clamp(min_y, max_y),
+                    )
+                }
+                None => (
+                    room.bounds.x
+                        + uniform_or(
+                            rng,
+                            padding,
+                            room.bounds.width - padding,
+                            center.x - room.bounds.x,
+                        ),
+                    room.bounds.y
+                        + uniform_or(
+                            rng,
+                            padding,
+                            room.bounds.height - padding,
+                            center.y - room.bounds.y,
+                        ),
+                ),
+            };
 
             room.entities.push(PlacedEntity {
-                id: format!("{}_{}_entity_{}", room.id, entity_type, i),
+                id: format!("{}_{}_entity_{}", room.id, entity_type, first_index + i),
                 entity_type: entity_type.to_string(),
                 position: LayoutPosition { x, y },
+                rotation: None,
+                metadata: HashMap::new(),
+            });
+        }
+    }
+
+    /// Add `count` decorative props of `prop_type` according to `placement`
+    pub fn add_props(
+        rng: &mut ChaCha8Rng,
+        room: &mut GeneratedRoom,
+        prop_type: &str,
+        count: usize,
+        placement: PropPlacement,
+    ) {
+        let b = room.bounds.clone();
+        let center = Self::get_center(room);
+
+        // Rooms too small for the inset or padding put props on their center line
+        for _ in 0..count {
+            let (x, y) = match placement {
+                PropPlacement::Wall => {
+                    let along_x = uniform_or(
+                        rng,
+                        b.x + PROP_WALL_INSET,
+                        b.x + b.width - PROP_WALL_INSET,
+                        center.x,
+                    );
+                    let along_y = uniform_or(
+                        rng,
+                        b.y + PROP_WALL_INSET,
+                        b.y + b.height - PROP_WALL_INSET,
+                        center.y,
+                    );
+                    match rng.gen_range(0..4) {
+                        0 => (along_x, b.y + PROP_WALL_INSET),
+                        1 => (b.x + b.width - PROP_WALL_INSET, along_y),
+                        2 => (along_x, b.y + b.height - PROP_WALL_INSET),
+                        _ => (b.x + PROP_WALL_INSET, along_y),
+                    }
+                }
+                PropPlacement::Center => (
+                    center.x + rng.gen_range(-0.25..0.25) * b.width,
+                    center.y + rng.gen_range(-0.25..0.25) * b.height,
+                ),
+                PropPlacement::Random => {
+                    let padding = 1.5;
+                    (
+                        b.x + uniform_or(rng, padding, b.width - padding, b.width / 2.0),
+                        b.y + uniform_or(rng, padding, b.height - padding, b.height / 2.0),
+                    )
+                }
+            };
+
+            room.entities.push(PlacedEntity {
+                id: format!("{}_{}_entity_{}", room.id, prop_type, room.entities.len()),
+                entity_type: prop_type.to_string(),
+                position: LayoutPosition { x, y },
+                rotation: None,
                 metadata: HashMap::new(),
             });
         }
@@ -167,12 +576,43 @@ impl RoomGenerator {
         }
     }
 
-    /// Get a door position on the edge of a room facing a direction
+    /// Move a room and everything placed inside it by `(dx, dy)`
+    pub fn translate(room: &mut GeneratedRoom, dx: f64, dy: f64) {
+        room.bounds.x += dx;
+        room.bounds.y += dy;
+        for entity in &mut room.entities {
+            entity.position.x += dx;
+            entity.position.y += dy;
+        }
+        if let Some(vertices) = footprint_vertices(room) {
+            let shifted: Vec<(f64, f64)> =
+                vertices.iter().map(|&(x, y)| (x + dx, y + dy)).collect();
+            room.metadata
+                .insert("footprint".to_string(), polygon_value(&shifted));
+        }
+        if let Some(center) = room.metadata.get_mut("center") {
+            if let (Some(x), Some(y)) = (center["x"].as_f64(), center["y"].as_f64()) {
+                *center = serde_json::json!({ "x": x + dx, "y": y + dy });
+            }
+        }
+    }
+
+    /// Get a door position on the edge of a room facing a direction.
+    ///
+    /// Rooms with a zero, negative or non-finite side (e.g. from an edited
+    /// layout) get the side's midpoint, since there is no range to draw from.
     pub fn get_door_position(
         room: &GeneratedRoom,
         direction: Direction,
         rng: &mut ChaCha8Rng,
     ) -> LayoutPosition {
+        let side = match direction {
+            Direction::Right | Direction::Left => room.bounds.height,
+            Direction::Down | Direction::Up => room.bounds.width,
+        };
+        if Self::has_centered_doors(room) || !(side > 0.0 && side.is_finite()) {
+            return Self::centered_door_position(room, direction);
+        }
         match direction {
             Direction::Right => LayoutPosition {
                 x: room.bounds.x + room.bounds.width,
@@ -196,9 +636,156 @@ impl RoomGenerator {
             },
         }
     }
+
+    /// Arenas and circular rooms only meet their bounds at each side's midpoint
+    fn has_centered_doors(room: &GeneratedRoom) -> bool {
+        let shape = room.metadata.get("shape").and_then(|v| v.as_str());
+        [RoomShape::Arena, RoomShape::Circular]
+            .iter()
+            .any(|s| shape == Some(&format!("{:?}", s)))
+    }
+
+    /// Midpoint of the room's side facing `direction`
+    fn centered_door_position(room: &GeneratedRoom, direction: Direction) -> LayoutPosition {
+        let center = Self::get_center(room);
+        match direction {
+            Direction::Right => LayoutPosition {
+                x: room.bounds.x + room.bounds.width,
+                y: center.y,
+            },
+            Direction::Left => LayoutPosition {
+                x: room.bounds.x,
+                y: center.y,
+            },
+            Direction::Down => LayoutPosition {
+                x: center.x,
+                y: room.bounds.y + room.bounds.height,
+            },
+            Direction::Up => LayoutPosition {
+                x: center.x,
+                y: room.bounds.y,
+            },
+        }
+    }
+}
+
+/// Octagon inscribed in `bounds` as a clockwise list of `{x, y}` vertices
+fn arena_footprint(bounds: &Rectangle) -> serde_json::Value {
+    let cut_x = bounds.width * ARENA_CORNER_CUT;
+    let cut_y = bounds.height * ARENA_CORNER_CUT;
+    let (left, top) = (bounds.x, bounds.y);
+    let (right, bottom) = (bounds.x + bounds.width, bounds.y + bounds.height);
+    let vertices = [
+        (left + cut_x, top),
+        (right - cut_x, top),
+        (right, top + cut_y),
+        (right, bottom - cut_y),
+        (right - cut_x, bottom),
+        (left + cut_x, bottom),
+        (left, bottom - cut_y),
+        (left, top + cut_y),
+    ];
+    polygon_value(&vertices)
+}
+
+/// Rectangle with the notch at `corner` (an index into `NOTCH_CORNERS`) cut out, clockwise
+fn l_shaped_footprint(
+    bounds: &Rectangle,
+    corner: usize,
+    notch_width: f64,
+    notch_height: f64,
+) -> serde_json::Value {
+    let (left, top) = (bounds.x, bounds.y);
+    let (right, bottom) = (bounds.x + bounds.width, bounds.y + bounds.height);
+    // Corner point and the direction pointing into the room from it
+    let corners = [
+        (left, top, 1.0, 1.0),
+        (right, top, -1.0, 1.0),
+        (right, bottom, -1.0, -1.0),
+        (left, bottom, 1.0, -1.0),
+    ];
+
+    let mut vertices = Vec::with_capacity(6);
+    for (i, &(x, y, sx, sy)) in corners.iter().enumerate() {
+        if i != corner {
+            vertices.push((x, y));
+            continue;
+        }
+        let on_vertical_edge = (x, y + sy * notch_height);
+        let on_horizontal_edge = (x + sx * notch_width, y);
+        let inner = (x + sx * notch_width, y + sy * notch_height);
+        // Clockwise, the top-left and bottom-right corners are entered along a vertical edge
+        if i % 2 == 0 {
+            vertices.extend([on_vertical_edge, inner, on_horizontal_edge]);
+        } else {
+            vertices.extend([on_horizontal_edge, inner, on_vertical_edge]);
+        }
+    }
+    polygon_value(&vertices)
+}
+
+/// Ellipse inscribed in `bounds` with each vertex pulled inward by a random amount
+fn irregular_footprint(rng: &mut ChaCha8Rng, bounds: &Rectangle) -> serde_json::Value {
+    let (half_w, half_h) = (bounds.width / 2.0, bounds.height / 2.0);
+    let (cx, cy) = (bounds.x + half_w, bounds.y + half_h);
+    let vertices: Vec<(f64, f64)> = (0..IRREGULAR_VERTICES)
+        .map(|i| {
+            let angle = std::f64::consts::TAU * i as f64 / IRREGULAR_VERTICES as f64;
+            let scale = rng.gen_range(IRREGULAR_JITTER);
+            (
+                cx + angle.cos() * half_w * scale,
+                cy + angle.sin() * half_h * scale,
+            )
+        })
+        .collect();
+    polygon_value(&vertices)
+}
+
+fn polygon_value(vertices: &[(f64, f64)]) -> serde_json::Value {
+    serde_json::Value::Array(
+        vertices
+            .iter()
+            .map(|&(x, y)| serde_json::json!({ "x": x, "y": y }))
+            .collect(),
+    )
 }
 
-#[derive(Debug, Clone, Copy)]
+/// The room's `footprint` metadata as `(x, y)` vertices, if it has one
+fn footprint_vertices(room: &GeneratedRoom) -> Option<Vec<(f64, f64)>> {
+    room.metadata
+        .get("footprint")?
+        .as_array()?
+        .iter()
+        .map(|v| Some((v.get("x")?.as_f64()?, v.get("y")?.as_f64()?)))
+        .collect()
+}
+
+/// Even-odd ray casting test
+fn point_in_polygon(x: f64, y: f64, polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for (i, &(xi, yi)) in polygon.iter().enumerate() {
+        let (xj, yj) = polygon[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Whether two rooms overlap by more than `tolerance` units on both axes.
+///
+/// With a tolerance of 0, rooms that merely share an edge count as overlapping;
+/// a small positive tolerance lets shared-wall layouts through.
+pub fn rectangles_overlap(a: &Rectangle, b: &Rectangle, tolerance: f64) -> bool {
+    let overlap_x = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+    let overlap_y = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+    overlap_x >= tolerance && overlap_y >= tolerance
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Direction {
     Right,
     Left,
@@ -215,4 +802,382 @@ impl Direction {
             Direction::Down => Direction::Up,
         }
     }
+
+    /// Parse a lowercase direction name ("right", "left", "up", "down")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "right" => Some(Direction::Right),
+            "left" => Some(Direction::Left),
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    /// Rotate 90 degrees, clockwise in screen space (y grows downward)
+    pub fn turned(&self, clockwise: bool) -> Self {
+        match (self, clockwise) {
+            (Direction::Right, true) | (Direction::Left, false) => Direction::Down,
+            (Direction::Down, true) | (Direction::Up, false) => Direction::Left,
+            (Direction::Left, true) | (Direction::Right, false) => Direction::Up,
+            (Direction::Up, true) | (Direction::Down, false) => Direction::Right,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rectangle {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_props_in_one_unit_room_do_not_panic() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut room = RoomGenerator::generate(
+            &mut rng,
+            &RoomConfig {
+                min_width: 1.0,
+                max_width: 1.0,
+                min_height: 1.0,
+                max_height: 1.0,
+                ..RoomConfig::default()
+            },
+            LayoutPosition { x: 4.0, y: 4.0 },
+            "tiny",
+        );
+
+        for placement in [
+            PropPlacement::Wall,
+            PropPlacement::Center,
+            PropPlacement::Random,
+        ] {
+            RoomGenerator::add_props(&mut rng, &mut room, "crate", 3, placement);
+        }
+
+        assert_eq!(room.entities.len(), 9);
+        assert!(room.entities.iter().all(|e| {
+            (4.0..=5.0).contains(&e.position.x) && (4.0..=5.0).contains(&e.position.y)
+        }));
+    }
+
+    #[test]
+    fn test_entities_in_one_unit_room_do_not_panic() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut room = RoomGenerator::generate(
+            &mut rng,
+            &RoomConfig {
+                min_width: 1.0,
+                max_width: 1.0,
+                min_height: 1.0,
+                max_height: 1.0,
+                ..RoomConfig::default()
+            },
+            LayoutPosition { x: 4.0, y: 4.0 },
+            "tiny",
+        );
+
+        RoomGenerator::add_entities(&mut rng, &mut room, "enemy", 4, 4, 0.0);
+        RoomGenerator::add_entities(&mut rng, &mut room, "enemy", 4, 4, 0.8);
+
+        assert_eq!(room.entities.len(), 8);
+        assert!(room
+            .entities
+            .iter()
+            .all(|e| (e.position.x, e.position.y) == (4.5, 4.5)));
+    }
+
+    #[test]
+    fn test_edge_sharing_rooms_pass_with_tolerance() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(10.0, 2.0, 10.0, 10.0);
+
+        assert!(rectangles_overlap(&a, &b, 0.0));
+        assert!(!rectangles_overlap(&a, &b, 0.01));
+    }
+
+    #[test]
+    fn test_real_overlap_fails_despite_tolerance() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(5.0, 5.0, 10.0, 10.0);
+
+        assert!(rectangles_overlap(&a, &b, 0.01));
+        assert!(!rectangles_overlap(&a, &rect(30.0, 0.0, 5.0, 5.0), 0.0));
+    }
+
+    #[test]
+    fn test_arena_room_is_symmetric_with_centered_doors() {
+        let config = RoomConfig {
+            shape: RoomShape::from("arena"),
+            ..RoomConfig::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        let room = RoomGenerator::generate(
+            &mut rng,
+            &config,
+            LayoutPosition { x: 10.0, y: 20.0 },
+            "boss",
+        );
+
+        assert_eq!(room.bounds.width, room.bounds.height);
+        assert!(room.bounds.width >= ARENA_MIN_SIZE);
+
+        // Every footprint vertex has a mirror image through the room center
+        let center = RoomGenerator::get_center(&room);
+        let vertices: Vec<(f64, f64)> = room.metadata["footprint"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| (v["x"].as_f64().unwrap(), v["y"].as_f64().unwrap()))
+            .collect();
+        assert_eq!(vertices.len(), 8);
+        for &(x, y) in &vertices {
+            let (mx, my) = (2.0 * center.x - x, 2.0 * center.y - y);
+            assert!(vertices
+                .iter()
+                .any(|&(vx, vy)| (vx - mx).abs() < 1e-9 && (vy - my).abs() < 1e-9));
+        }
+
+        let doors: Vec<LayoutPosition> = [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ]
+        .iter()
+        .map(|&d| RoomGenerator::get_door_position(&room, d, &mut rng))
+        .collect();
+        assert_eq!(doors[0].x, center.x);
+        assert_eq!(doors[2].x, center.x);
+        assert_eq!(doors[1].y, center.y);
+        assert_eq!(doors[3].y, center.y);
+        assert_eq!(doors[0].y, room.bounds.y);
+        assert_eq!(doors[1].x, room.bounds.x + room.bounds.width);
+    }
+
+    #[test]
+    fn test_high_clustering_tightens_entity_spacing() {
+        let mean_pairwise_distance = |clustering: f64| {
+            let mut total = 0.0;
+            let mut pairs = 0;
+            for seed in 0..50 {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let mut room = RoomGenerator::generate(
+                    &mut rng,
+                    &RoomConfig {
+                        min_width: 30.0,
+                        max_width: 30.0,
+                        min_height: 30.0,
+                        max_height: 30.0,
+                        ..RoomConfig::default()
+                    },
+                    LayoutPosition { x: 0.0, y: 0.0 },
+                    "room",
+                );
+                RoomGenerator::add_entities(&mut rng, &mut room, "enemy", 8, 8, clustering);
+                for (i, a) in room.entities.iter().enumerate() {
+                    for b in &room.entities[i + 1..] {
+                        total += ((a.position.x - b.position.x).powi(2)
+                            + (a.position.y - b.position.y).powi(2))
+                        .sqrt();
+                        pairs += 1;
+                    }
+                }
+            }
+            total / pairs as f64
+        };
+
+        assert!(mean_pairwise_distance(1.0) < mean_pairwise_distance(0.0) * 0.75);
+    }
+
+    #[test]
+    fn test_normal_sizes_cluster_near_mean() {
+        let spread = |distribution: SizeDistribution| {
+            let mut rng = ChaCha8Rng::seed_from_u64(9);
+            let samples: Vec<f64> = (0..2000)
+                .map(|_| distribution.sample(&mut rng, 4.0, 16.0))
+                .collect();
+            assert!(samples.iter().all(|v| (4.0..=16.0).contains(v)));
+            let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            let variance =
+                samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+            (mean, variance.sqrt())
+        };
+
+        let (uniform_mean, uniform_sd) = spread(SizeDistribution::Uniform);
+        let (normal_mean, normal_sd) = spread(SizeDistribution::Normal);
+
+        // Uniform over 12 units has sd ~3.46; the normal draw targets sd 2
+        assert!((uniform_mean - 10.0).abs() < 0.3);
+        assert!((normal_mean - 10.0).abs() < 0.3);
+        assert!(normal_sd < uniform_sd * 0.7);
+    }
+
+    fn sized_room(width: f64, height: f64) -> GeneratedRoom {
+        GeneratedRoom {
+            id: "room".to_string(),
+            room_type: "default".to_string(),
+            bounds: Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width,
+                height,
+            },
+            tiles: None,
+            entities: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_rectangular_tiles_have_wall_border() {
+        let tiles = RoomGenerator::generate_tiles(&sized_room(6.0, 6.0), RoomShape::Rectangular);
+
+        assert_eq!(tiles.len(), 6);
+        for (row, line) in tiles.iter().enumerate() {
+            assert_eq!(line.len(), 6);
+            for (col, &tile) in line.iter().enumerate() {
+                let border = row == 0 || row == 5 || col == 0 || col == 5;
+                assert_eq!(tile, if border { 0 } else { 1 }, "cell {},{}", row, col);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shaped_tiles_follow_mask() {
+        let l_shape = RoomGenerator::generate_tiles(&sized_room(10.0, 10.0), RoomShape::LShaped);
+        // Missing top-right quadrant, floor in the bottom-right leg
+        assert!(l_shape[..5]
+            .iter()
+            .all(|line| line[5..].iter().all(|&t| t == 0)));
+        assert_eq!(l_shape[7][7], 1);
+
+        let circle = RoomGenerator::generate_tiles(&sized_room(10.0, 10.0), RoomShape::Circular);
+        assert_eq!(circle[1][1], 0);
+        assert_eq!(circle[5][5], 1);
+    }
+
+    #[test]
+    fn test_emit_tiles_is_opt_in() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let position = LayoutPosition { x: 0.0, y: 0.0 };
+        let room = RoomGenerator::generate(&mut rng, &RoomConfig::default(), position.clone(), "a");
+        assert!(room.tiles.is_none());
+
+        let config = RoomConfig {
+            emit_tiles: true,
+            ..RoomConfig::default()
+        };
+        let room = RoomGenerator::generate(&mut rng, &config, position, "b");
+        let tiles = room.tiles.unwrap();
+        assert_eq!(tiles.len(), room.bounds.height.round() as usize);
+    }
+
+    #[test]
+    fn test_shapes_describe_reproducible_footprints() {
+        let generate = |shape: &str| {
+            let mut rng = ChaCha8Rng::seed_from_u64(42);
+            let config = RoomConfig {
+                shape: RoomShape::from(shape),
+                min_width: 10.0,
+                max_width: 20.0,
+                min_height: 10.0,
+                max_height: 20.0,
+                ..RoomConfig::default()
+            };
+            RoomGenerator::generate(&mut rng, &config, LayoutPosition { x: 5.0, y: 5.0 }, "r")
+        };
+
+        let shapes = ["l-shaped", "circular", "irregular"];
+        for shape in shapes {
+            let (a, b) = (generate(shape), generate(shape));
+            assert_eq!(
+                serde_json::to_value(&a.metadata).unwrap(),
+                serde_json::to_value(&b.metadata).unwrap(),
+                "{} metadata is not reproducible",
+                shape
+            );
+        }
+
+        let l_shaped = generate("l-shaped");
+        assert!(NOTCH_CORNERS.contains(&l_shaped.metadata["notch"]["corner"].as_str().unwrap()));
+        assert_eq!(footprint_vertices(&l_shaped).unwrap().len(), 6);
+
+        let circular = generate("circular");
+        assert_eq!(circular.bounds.width, circular.bounds.height);
+        assert_eq!(circular.metadata["radius"], circular.bounds.width / 2.0);
+        let center = RoomGenerator::get_center(&circular);
+        assert_eq!(circular.metadata["center"]["x"], center.x);
+
+        let irregular = generate("irregular");
+        let vertices = footprint_vertices(&irregular).unwrap();
+        assert_eq!(vertices.len(), IRREGULAR_VERTICES);
+        let b = &irregular.bounds;
+        assert!(vertices
+            .iter()
+            .all(|&(x, y)| x >= b.x && x <= b.x + b.width && y >= b.y && y <= b.y + b.height));
+
+        let rectangular = generate("rectangular");
+        assert!(!rectangular.metadata.contains_key("footprint"));
+    }
+
+    #[test]
+    fn test_translate_moves_footprint_and_center() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let config = RoomConfig {
+            shape: RoomShape::Circular,
+            ..RoomConfig::default()
+        };
+        let mut room =
+            RoomGenerator::generate(&mut rng, &config, LayoutPosition { x: 0.0, y: 0.0 }, "r");
+        RoomGenerator::translate(&mut room, 3.0, -2.0);
+        let center = RoomGenerator::get_center(&room);
+        assert_eq!(room.metadata["center"]["x"], center.x);
+        assert_eq!(room.metadata["center"]["y"], center.y);
+
+        let config = RoomConfig {
+            shape: RoomShape::LShaped,
+            ..RoomConfig::default()
+        };
+        let mut room =
+            RoomGenerator::generate(&mut rng, &config, LayoutPosition { x: 0.0, y: 0.0 }, "r");
+        let before = footprint_vertices(&room).unwrap();
+        RoomGenerator::translate(&mut room, 3.0, -2.0);
+        let after = footprint_vertices(&room).unwrap();
+        for (&(x0, y0), &(x1, y1)) in before.iter().zip(&after) {
+            assert!((x1 - x0 - 3.0).abs() < 1e-9 && (y1 - y0 + 2.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_floor_variants_follow_weights() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let config = RoomConfig {
+            min_width: 60.0,
+            max_width: 60.0,
+            min_height: 60.0,
+            max_height: 60.0,
+            emit_tiles: true,
+            floor_variants: vec![6.0, 3.0, 1.0],
+            ..RoomConfig::default()
+        };
+        let room =
+            RoomGenerator::generate(&mut rng, &config, LayoutPosition { x: 0.0, y: 0.0 }, "a");
+        let tiles: Vec<i32> = room.tiles.unwrap().into_iter().flatten().collect();
+
+        let floor = tiles.iter().filter(|&&t| t > 0).count() as f64;
+        let share = |variant: i32| tiles.iter().filter(|&&t| t == variant).count() as f64 / floor;
+        assert_eq!(floor, 58.0 * 58.0);
+        assert!((share(1) - 0.6).abs() < 0.03);
+        assert!((share(2) - 0.3).abs() < 0.03);
+        assert!((share(3) - 0.1).abs() < 0.03);
+        assert!(tiles.iter().all(|&t| (0..=3).contains(&t)));
+    }
 }
@@ -0,0 +1,26 @@
+//! Continuous random draws shared by the executor and room generator
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// Draw from the standard normal distribution (mean 0, standard deviation 1)
+pub fn standard_normal(rng: &mut ChaCha8Rng) -> f64 {
+    // Box-Muller; 1 - u keeps the log argument in (0, 1]
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+/// Draw uniformly from `min..=max`; `None` when the bounds or their span aren't finite
+pub fn uniform(rng: &mut ChaCha8Rng, min: f64, max: f64) -> Option<f64> {
+    ((max - min).is_finite() && min <= max).then(|| rng.gen_range(min..=max))
+}
+
+/// Draw uniformly from `low..high`, or return `fallback` when the range is empty
+pub fn uniform_or(rng: &mut ChaCha8Rng, low: f64, high: f64, fallback: f64) -> f64 {
+    if low < high && (high - low).is_finite() {
+        rng.gen_range(low..high)
+    } else {
+        fallback
+    }
+}
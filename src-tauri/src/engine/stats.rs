@@ -0,0 +1,109 @@
+//! Summary statistics shared by simulation and layout analysis
+
+use crate::models::{DistributionStats, HistogramBucket, Percentiles};
+
+/// Min/max, mean, median, standard deviation, percentiles and a 10-bucket histogram of `data`
+pub fn calculate_stats(data: &[f64]) -> DistributionStats {
+    if data.is_empty() {
+        return DistributionStats {
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            median: 0.0,
+            std_dev: 0.0,
+            percentiles: Percentiles {
+                p5: 0.0,
+                p25: 0.0,
+                p75: 0.0,
+                p95: 0.0,
+            },
+            histogram: vec![],
+        };
+    }
+
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let min = sorted[0];
+    let max = sorted[sorted.len() - 1];
+    let mean = data.iter().sum::<f64>() / data.len() as f64;
+
+    let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64;
+    let std_dev = variance.sqrt();
+
+    // Linear interpolation between the two nearest ranks (type 7)
+    let percentile = |p: f64| -> f64 {
+        let rank = (p / 100.0) * (sorted.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = (lower + 1).min(sorted.len() - 1);
+        sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+    };
+    let median = percentile(50.0);
+
+    // Generate histogram with 10 buckets
+    let bucket_size = (max - min) / 10.0;
+    let mut histogram = vec![
+        HistogramBucket {
+            bucket: 0.0,
+            count: 0
+        };
+        10
+    ];
+    for (i, h) in histogram.iter_mut().enumerate() {
+        h.bucket = min + (i as f64 * bucket_size);
+    }
+
+    for val in data {
+        // Identical values leave no range to divide; they all land in the first bucket
+        let bucket_idx = if bucket_size > 0.0 {
+            ((val - min) / bucket_size).floor() as usize
+        } else {
+            0
+        };
+        let bucket_idx = bucket_idx.min(9);
+        histogram[bucket_idx].count += 1;
+    }
+
+    DistributionStats {
+        min,
+        max,
+        mean,
+        median,
+        std_dev,
+        percentiles: Percentiles {
+            p5: percentile(5.0),
+            p25: percentile(25.0),
+            p75: percentile(75.0),
+            p95: percentile(95.0),
+        },
+        histogram,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_values_fill_first_bucket() {
+        let stats = calculate_stats(&[5.0, 5.0, 5.0]);
+
+        assert_eq!(stats.histogram[0].count, 3);
+        assert_eq!(stats.histogram.iter().map(|h| h.count).sum::<u32>(), 3);
+        assert!(stats.histogram.iter().all(|h| h.bucket == 5.0));
+        assert_eq!(stats.std_dev, 0.0);
+    }
+
+    #[test]
+    fn test_percentiles_interpolate_between_ranks() {
+        let data: Vec<f64> = (1..=10).map(f64::from).collect();
+        let stats = calculate_stats(&data);
+
+        // Ranks 2.25, 4.5 and 6.75 (0-based) over 1..=10
+        assert!((stats.percentiles.p25 - 3.25).abs() < 1e-9);
+        assert!((stats.median - 5.5).abs() < 1e-9);
+        assert!((stats.percentiles.p75 - 7.75).abs() < 1e-9);
+        assert!((stats.percentiles.p5 - 1.45).abs() < 1e-9);
+        assert!((stats.percentiles.p95 - 9.55).abs() < 1e-9);
+    }
+}
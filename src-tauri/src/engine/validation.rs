@@ -0,0 +1,463 @@
+//! Static checks on a generator before it is executed
+
+use crate::models::generator::{
+    Generator, GraphNode, NodeGraph, NodeType, Parameter, ParameterType, Port,
+};
+use crate::models::Asset;
+use std::collections::{HashMap, HashSet};
+
+/// Node data fields whose values name a shared asset
+const ASSET_REFERENCE_KEYS: &[&str] = &["prefabId", "template"];
+
+/// Collect human-readable problems with a generator; empty means valid.
+///
+/// The graph needs a Start and an Output node, edges must point at existing
+/// nodes and declared ports, every declared input port must be connected
+/// (a Merge node needs only one of its inputs), and cycles must pass through a
+/// Loop node. Asset references are only checked when the project's assets
+/// are supplied.
+pub fn validate_generator(generator: &Generator, assets: Option<&[Asset]>) -> Vec<String> {
+    let mut problems = Vec::new();
+    let graph = &generator.graph;
+
+    for (node_type, name) in [(NodeType::Start, "Start"), (NodeType::Output, "Output")] {
+        if !graph.nodes.iter().any(|n| n.node_type == node_type) {
+            problems.push(format!("Graph has no {} node", name));
+        }
+    }
+    check_edges(graph, &mut problems);
+    check_unconnected_inputs(graph, &mut problems);
+    check_unguarded_cycles(graph, &mut problems);
+
+    if let Some(assets) = assets {
+        check_asset_references(generator, assets, &mut problems);
+    }
+
+    problems
+}
+
+/// Check user-entered parameter values against the generator's declared parameters.
+///
+/// Returns one message per bad value, in name order; names the generator does
+/// not declare are reported too.
+pub fn validate_parameters(
+    generator: &Generator,
+    values: &HashMap<String, serde_json::Value>,
+) -> Vec<String> {
+    let mut names: Vec<&String> = values.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .filter_map(
+            |name| match generator.parameters.iter().find(|p| &p.name == name) {
+                Some(parameter) => check_parameter_value(parameter, &values[name]),
+                None => Some(format!("Unknown parameter '{}'", name)),
+            },
+        )
+        .collect()
+}
+
+fn check_parameter_value(parameter: &Parameter, value: &serde_json::Value) -> Option<String> {
+    let name = &parameter.name;
+    match parameter.param_type {
+        ParameterType::Number => {
+            let Some(number) = value.as_f64() else {
+                return Some(format!("Parameter '{}' must be a number", name));
+            };
+            if let Some(min) = parameter.min.filter(|&min| number < min) {
+                return Some(format!(
+                    "Parameter '{}' is {} but must be at least {}",
+                    name, number, min
+                ));
+            }
+            if let Some(max) = parameter.max.filter(|&max| number > max) {
+                return Some(format!(
+                    "Parameter '{}' is {} but must be at most {}",
+                    name, number, max
+                ));
+            }
+            None
+        }
+        ParameterType::Select => match value.as_str() {
+            Some(option) if parameter.options.iter().any(|o| o == option) => None,
+            _ => Some(format!(
+                "Parameter '{}' must be one of: {}",
+                name,
+                parameter.options.join(", ")
+            )),
+        },
+        ParameterType::Boolean => {
+            (!value.is_boolean()).then(|| format!("Parameter '{}' must be a boolean", name))
+        }
+        ParameterType::String => {
+            (!value.is_string()).then(|| format!("Parameter '{}' must be a string", name))
+        }
+    }
+}
+
+fn check_edges(graph: &NodeGraph, problems: &mut Vec<String>) {
+    let nodes: HashMap<&str, &GraphNode> = graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+
+    for edge in &graph.edges {
+        let ends = [
+            (
+                &edge.source,
+                "output",
+                nodes.get(edge.source.node_id.as_str()).map(|n| &n.outputs),
+            ),
+            (
+                &edge.target,
+                "input",
+                nodes.get(edge.target.node_id.as_str()).map(|n| &n.inputs),
+            ),
+        ];
+        for (port_ref, kind, ports) in ends {
+            match ports {
+                None => problems.push(format!(
+                    "Edge {} references missing node '{}'",
+                    edge.id, port_ref.node_id
+                )),
+                // Nodes that declare no ports accept any port id
+                Some(ports)
+                    if !ports.is_empty() && !ports.iter().any(|p| p.id == port_ref.port_id) =>
+                {
+                    problems.push(format!(
+                        "Edge {} references missing {} port '{}' on node {}",
+                        edge.id, kind, port_ref.port_id, port_ref.node_id
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+fn check_unconnected_inputs(graph: &NodeGraph, problems: &mut Vec<String>) {
+    let connected: HashSet<(&str, &str)> = graph
+        .edges
+        .iter()
+        .map(|e| (e.target.node_id.as_str(), e.target.port_id.as_str()))
+        .collect();
+
+    for node in &graph.nodes {
+        let is_connected = |port: &Port| connected.contains(&(node.id.as_str(), port.id.as_str()));
+        // Merge inputs are alternative paths, so any one of them is enough
+        if node.node_type == NodeType::Merge && node.inputs.iter().any(is_connected) {
+            continue;
+        }
+        for port in &node.inputs {
+            if !is_connected(port) {
+                problems.push(format!(
+                    "Node {} input '{}' is not connected",
+                    node.id, port.id
+                ));
+            }
+        }
+    }
+}
+
+/// Report each cycle that survives removing Loop nodes, i.e. one no Loop can bound
+fn check_unguarded_cycles(graph: &NodeGraph, problems: &mut Vec<String>) {
+    let loops: HashSet<&str> = graph
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == NodeType::Loop)
+        .map(|n| n.id.as_str())
+        .collect();
+    let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &graph.edges {
+        let (from, to) = (edge.source.node_id.as_str(), edge.target.node_id.as_str());
+        if !loops.contains(from) && !loops.contains(to) {
+            successors.entry(from).or_default().push(to);
+        }
+    }
+
+    let mut finished: HashSet<&str> = HashSet::new();
+    for node in &graph.nodes {
+        let mut path = vec![];
+        find_cycles(
+            node.id.as_str(),
+            &successors,
+            &mut path,
+            &mut finished,
+            problems,
+        );
+    }
+}
+
+/// Depth-first search; reaching a node already on `path` closes a cycle
+fn find_cycles<'a>(
+    node: &'a str,
+    successors: &HashMap<&'a str, Vec<&'a str>>,
+    path: &mut Vec<&'a str>,
+    finished: &mut HashSet<&'a str>,
+    problems: &mut Vec<String>,
+) {
+    if finished.contains(node) {
+        return;
+    }
+    if let Some(start) = path.iter().position(|&n| n == node) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(node);
+        problems.push(format!(
+            "Cycle {} is not guarded by a Loop node",
+            cycle.join(" -> ")
+        ));
+        return;
+    }
+
+    path.push(node);
+    for &next in successors.get(node).into_iter().flatten() {
+        find_cycles(next, successors, path, finished, problems);
+    }
+    path.pop();
+    finished.insert(node);
+}
+
+fn check_asset_references(generator: &Generator, assets: &[Asset], problems: &mut Vec<String>) {
+    let known: HashSet<&str> = assets.iter().map(|a| a.id.as_str()).collect();
+
+    for node in &generator.graph.nodes {
+        for key in ASSET_REFERENCE_KEYS {
+            if let Some(asset_id) = node.data.extra.get(*key).and_then(|v| v.as_str()) {
+                if !known.contains(asset_id) {
+                    problems.push(format!(
+                        "Node {} references missing asset '{}' via {}",
+                        node.id, asset_id, key
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator_with_prefab(prefab_id: &str) -> Generator {
+        serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Generator",
+            "type": "dungeon",
+            "graph": {
+                "nodes": [
+                    { "id": "start", "type": "start", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Start" } },
+                    { "id": "room", "type": "room", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Room", "prefabId": prefab_id } },
+                    { "id": "output", "type": "output", "position": { "x": 0.0, "y": 0.0 },
+                      "data": { "label": "Output" } }
+                ],
+                "edges": [
+                    { "id": "e1", "source": { "nodeId": "start", "portId": "out" },
+                      "target": { "nodeId": "room", "portId": "in" } },
+                    { "id": "e2", "source": { "nodeId": "room", "portId": "out" },
+                      "target": { "nodeId": "output", "portId": "in" } }
+                ]
+            }
+        }))
+        .unwrap()
+    }
+
+    fn asset(id: &str) -> Asset {
+        Asset {
+            id: id.to_string(),
+            name: id.to_string(),
+            asset_type: "prefab".to_string(),
+            data: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_missing_asset_reference_is_reported() {
+        let generator = generator_with_prefab("deleted_prefab");
+        let problems = validate_generator(&generator, Some(&[asset("crypt_prefab")]));
+
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("deleted_prefab"));
+        assert!(problems[0].contains("room"));
+    }
+
+    #[test]
+    fn test_existing_asset_reference_passes() {
+        let generator = generator_with_prefab("crypt_prefab");
+
+        assert!(validate_generator(&generator, Some(&[asset("crypt_prefab")])).is_empty());
+    }
+
+    fn generator_with_parameters() -> Generator {
+        serde_json::from_value(serde_json::json!({
+            "id": "gen",
+            "name": "Generator",
+            "type": "dungeon",
+            "graph": { "nodes": [], "edges": [] },
+            "parameters": [
+                { "name": "roomCount", "type": "number", "default": 6, "min": 2, "max": 12 },
+                { "name": "theme", "type": "select", "default": "crypt",
+                  "options": ["crypt", "cave"] },
+                { "name": "emitTiles", "type": "boolean", "default": false }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_number_out_of_range_is_reported() {
+        let values = HashMap::from([("roomCount".to_string(), serde_json::json!(20))]);
+        let problems = validate_parameters(&generator_with_parameters(), &values);
+
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("roomCount"));
+        assert!(problems[0].contains("at most 12"));
+    }
+
+    #[test]
+    fn test_select_value_outside_options_is_reported() {
+        let values = HashMap::from([
+            ("theme".to_string(), serde_json::json!("swamp")),
+            ("emitTiles".to_string(), serde_json::json!(true)),
+            ("depth".to_string(), serde_json::json!(3)),
+        ]);
+        let problems = validate_parameters(&generator_with_parameters(), &values);
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0], "Unknown parameter 'depth'");
+        assert!(problems[1].contains("crypt, cave"));
+    }
+
+    /// Graph from `(id, type)` nodes with In/Out ports and `(from, to)` edges
+    fn graph_generator(nodes: &[(&str, &str)], edges: &[(&str, &str)]) -> Generator {
+        let nodes: Vec<serde_json::Value> = nodes
+            .iter()
+            .map(|(id, node_type)| {
+                let inputs = if *node_type == "start" {
+                    serde_json::json!([])
+                } else {
+                    serde_json::json!([{ "id": "in", "type": "input", "data_type": "room" }])
+                };
+                serde_json::json!({
+                    "id": id, "type": node_type, "position": { "x": 0.0, "y": 0.0 },
+                    "data": { "label": id }, "inputs": inputs,
+                    "outputs": [{ "id": "out", "type": "output", "data_type": "room" }],
+                })
+            })
+            .collect();
+        let edges: Vec<serde_json::Value> = edges
+            .iter()
+            .enumerate()
+            .map(|(i, (from, to))| {
+                serde_json::json!({
+                    "id": format!("e{}", i),
+                    "source": { "nodeId": from, "portId": "out" },
+                    "target": { "nodeId": to, "portId": "in" },
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "id": "gen", "name": "Generator", "type": "dungeon",
+            "graph": { "nodes": nodes, "edges": edges },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_graph_has_no_problems() {
+        let generator = graph_generator(
+            &[
+                ("start", "start"),
+                ("loop", "loop"),
+                ("room", "room"),
+                ("output", "output"),
+            ],
+            &[
+                ("start", "loop"),
+                ("loop", "room"),
+                ("room", "loop"),
+                ("room", "output"),
+            ],
+        );
+
+        assert!(validate_generator(&generator, None).is_empty());
+    }
+
+    #[test]
+    fn test_missing_start_and_output_are_reported() {
+        let generator = graph_generator(&[("room", "room")], &[]);
+        let problems = validate_generator(&generator, None);
+
+        assert!(problems.contains(&"Graph has no Start node".to_string()));
+        assert!(problems.contains(&"Graph has no Output node".to_string()));
+    }
+
+    #[test]
+    fn test_edges_to_missing_nodes_and_ports_are_reported() {
+        let mut generator = graph_generator(
+            &[("start", "start"), ("room", "room"), ("output", "output")],
+            &[("start", "room"), ("room", "output"), ("room", "ghost")],
+        );
+        generator.graph.edges[1].target.port_id = "side".to_string();
+        let problems = validate_generator(&generator, None);
+
+        assert!(problems.contains(&"Edge e2 references missing node 'ghost'".to_string()));
+        assert!(problems
+            .contains(&"Edge e1 references missing input port 'side' on node output".to_string()));
+    }
+
+    #[test]
+    fn test_unconnected_input_is_reported() {
+        let generator = graph_generator(
+            &[("start", "start"), ("room", "room"), ("output", "output")],
+            &[("start", "output")],
+        );
+
+        assert_eq!(
+            validate_generator(&generator, None),
+            ["Node room input 'in' is not connected"]
+        );
+    }
+
+    #[test]
+    fn test_merge_needs_only_one_connected_input() {
+        let mut generator = graph_generator(
+            &[("start", "start"), ("merge", "merge"), ("output", "output")],
+            &[("start", "merge"), ("merge", "output")],
+        );
+        let merge = &mut generator.graph.nodes[1];
+        merge.inputs[0].id = "in1".to_string();
+        let mut second = merge.inputs[0].clone();
+        second.id = "in2".to_string();
+        merge.inputs.push(second);
+        generator.graph.edges[0].target.port_id = "in1".to_string();
+
+        assert!(validate_generator(&generator, None).is_empty());
+
+        generator.graph.edges.remove(0);
+        assert_eq!(
+            validate_generator(&generator, None),
+            [
+                "Node merge input 'in1' is not connected",
+                "Node merge input 'in2' is not connected"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_without_loop_is_reported() {
+        let generator = graph_generator(
+            &[
+                ("start", "start"),
+                ("a", "room"),
+                ("b", "room"),
+                ("output", "output"),
+            ],
+            &[("start", "a"), ("a", "b"), ("b", "a"), ("b", "output")],
+        );
+
+        assert_eq!(
+            validate_generator(&generator, None),
+            ["Cycle a -> b -> a is not guarded by a Loop node"]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Fixed-point weighted selection shared by the executor and room generator
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// Fixed-point factor weights are scaled by before weighted selection
+const WEIGHT_SCALE: f64 = 1_000_000.0;
+
+/// Weights as fixed-point integers, so cumulative sums compare the same on every platform.
+///
+/// Negative and non-finite weights count as 0. Each weight is capped at
+/// `u64::MAX / len`, so summing them can't overflow.
+pub fn scale_weights(weights: impl Iterator<Item = f64>) -> Vec<u64> {
+    let weights: Vec<f64> = weights.collect();
+    let cap = u64::MAX / weights.len().max(1) as u64;
+    weights
+        .iter()
+        .map(|&w| {
+            if w.is_finite() && w > 0.0 {
+                ((w * WEIGHT_SCALE).round() as u64).min(cap)
+            } else {
+                0
+            }
+        })
+        .collect()
+}
+
+/// Draw an index by scaled weight; `None` when no weight is positive
+pub fn pick_weighted(rng: &mut ChaCha8Rng, weights: &[u64]) -> Option<usize> {
+    let total: u64 = weights.iter().sum();
+    (total > 0).then(|| select_weighted(weights, rng.gen_range(0..total)))
+}
+
+/// Index whose cumulative range `[previous sum, sum)` contains `roll`, where `roll < sum(weights)`
+pub fn select_weighted(weights: &[u64], roll: u64) -> usize {
+    let mut cumulative = 0;
+    for (i, weight) in weights.iter().enumerate() {
+        cumulative += weight;
+        if roll < cumulative {
+            return i;
+        }
+    }
+    weights.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_weighted_selection_is_exact_at_boundaries() {
+        // 0.1 + 0.2 != 0.3 in floating point; scaled weights sum exactly
+        let weights = scale_weights([0.1, 0.2, 0.3].into_iter());
+        assert_eq!(weights, [100_000, 200_000, 300_000]);
+
+        assert_eq!(select_weighted(&weights, 0), 0);
+        assert_eq!(select_weighted(&weights, 99_999), 0);
+        assert_eq!(select_weighted(&weights, 100_000), 1);
+        assert_eq!(select_weighted(&weights, 299_999), 1);
+        assert_eq!(select_weighted(&weights, 300_000), 2);
+        assert_eq!(select_weighted(&weights, 599_999), 2);
+
+        // Zero weights are never selected, even exactly on their boundary
+        let weights = scale_weights([1.0, 0.0, -2.0, f64::NAN, 1.0].into_iter());
+        assert_eq!(select_weighted(&weights, 999_999), 0);
+        assert_eq!(select_weighted(&weights, 1_000_000), 4);
+    }
+
+    #[test]
+    fn test_huge_weights_do_not_overflow() {
+        let weights = scale_weights([1e300, 1e300].into_iter());
+        assert_eq!(weights[0], weights[1]);
+        assert!(weights
+            .iter()
+            .try_fold(0u64, |sum, w| sum.checked_add(*w))
+            .is_some());
+
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let picks: Vec<usize> = (0..64)
+            .filter_map(|_| pick_weighted(&mut rng, &weights))
+            .collect();
+        assert_eq!(picks.len(), 64);
+        assert!(picks.contains(&0) && picks.contains(&1));
+    }
+}
@@ -3,14 +3,25 @@ mod engine;
 mod models;
 
 use commands::{
-    cancel_simulation, create_project, generate_once, get_recent_projects, open_project,
-    run_simulation, save_project,
+    adjacency_report, append_room, backtrack_ratio, batch_diversity, camera_bounds,
+    cancel_simulation, check_layout_constraints, chokepoints, compactness, connection_length_stats,
+    create_project, crossing_connections, dedupe_spawns, difficulty_rating, door_counts,
+    export_layout, export_tiled, generate_batch, generate_once, get_recent_projects,
+    layout_checksum, layout_graph_json, layout_mst, linearity, longest_dead_end,
+    loot_distribution_evenness, mean_depth, minimap, node_execution_profile, open_project,
+    output_descriptor, output_entropy, overlapping_rooms, populated_fraction, preview_node,
+    project_node_usage, reachable_area_fraction, reachable_room_types, rebuild_connections,
+    regenerate_with_locks, remove_room, reskin_layout, room_size_by_type, run_simulation,
+    save_project, save_project_streaming, seed_gallery, simulate_and_select, spawns_by_type,
+    tags_in_use, total_tiles, tour_order, validate_generator, validate_parameters, verify_layout,
+    weighted_centroid,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .manage(commands::SimulationState::default())
         .invoke_handler(tauri::generate_handler![
             create_project,
             open_project,
@@ -19,6 +30,55 @@ pub fn run() {
             generate_once,
             run_simulation,
             cancel_simulation,
+            reachable_area_fraction,
+            output_descriptor,
+            batch_diversity,
+            longest_dead_end,
+            validate_generator,
+            layout_checksum,
+            verify_layout,
+            simulate_and_select,
+            export_tiled,
+            output_entropy,
+            regenerate_with_locks,
+            layout_mst,
+            overlapping_rooms,
+            spawns_by_type,
+            door_counts,
+            seed_gallery,
+            room_size_by_type,
+            preview_node,
+            populated_fraction,
+            append_room,
+            remove_room,
+            weighted_centroid,
+            crossing_connections,
+            backtrack_ratio,
+            check_layout_constraints,
+            difficulty_rating,
+            tags_in_use,
+            camera_bounds,
+            reskin_layout,
+            project_node_usage,
+            connection_length_stats,
+            compactness,
+            loot_distribution_evenness,
+            tour_order,
+            minimap,
+            dedupe_spawns,
+            export_layout,
+            node_execution_profile,
+            layout_graph_json,
+            validate_parameters,
+            reachable_room_types,
+            total_tiles,
+            save_project_streaming,
+            linearity,
+            mean_depth,
+            chokepoints,
+            generate_batch,
+            adjacency_report,
+            rebuild_connections,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -48,7 +48,7 @@ pub struct GraphNode {
     pub outputs: Vec<Port>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum NodeType {
     Start,
@@ -124,6 +124,9 @@ pub struct EdgeMetadata {
     pub label: Option<String>,
     #[serde(default)]
     pub animated: bool,
+    /// Relative chance a RandomSelect node follows this edge (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
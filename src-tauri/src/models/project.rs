@@ -51,6 +51,15 @@ impl Default for ExportConfig {
     }
 }
 
+/// Payload of the `save-progress` event, sent after each generator is written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveProgress {
+    #[serde(rename = "generatorId")]
+    pub generator_id: String,
+    pub saved: usize,
+    pub total: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecentProject {
     pub id: String,
@@ -20,6 +20,9 @@ pub struct GenerationResult {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<DungeonLayout>,
+    /// Generated items for Loot generators, which produce no spatial layout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loot: Option<LootResult>,
     #[serde(default, rename = "constraintResults")]
     pub constraint_results: Vec<ConstraintResult>,
     pub metadata: GenerationMetadata,
@@ -38,6 +41,23 @@ pub struct DungeonLayout {
     #[serde(rename = "playerStart")]
     pub player_start: LayoutPosition,
     pub exits: Vec<LayoutPosition>,
+    /// Authoring info (theme, author, ...) collected from nodes' `layoutMetadata`
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LootResult {
+    pub items: Vec<LootItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LootItem {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub rarity: String,
+    pub quantity: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +94,9 @@ pub struct PlacedEntity {
     #[serde(rename = "type")]
     pub entity_type: String,
     pub position: LayoutPosition,
+    /// Facing direction in radians, for directional props and enemies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<f64>,
     #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
 }
@@ -115,6 +138,10 @@ pub struct GenerationMetadata {
     pub node_executions: u32,
     #[serde(rename = "retryCount")]
     pub retry_count: u32,
+    /// Position in the seeded ChaCha stream (32-bit words consumed) when generation
+    /// finished; differing counts for the same seed and graph show where RNG use diverged
+    #[serde(default, rename = "rngDraws")]
+    pub rng_draws: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,6 +154,30 @@ pub struct SimulationConfig {
     pub seed_start: Option<u64>,
     #[serde(default)]
     pub parameters: HashMap<String, serde_json::Value>,
+    /// Constraints checked against every run
+    #[serde(default)]
+    pub constraints: Vec<crate::models::generator::Constraint>,
+    /// Stop checking a run's constraints at its first Error-severity failure
+    #[serde(default, rename = "shortCircuit")]
+    pub short_circuit: bool,
+    /// Graph to execute per seed (optional - if not provided, uses simple generation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<crate::models::generator::Generator>,
+}
+
+/// What `simulate_and_select` optimizes when picking the best run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionObjective {
+    /// Preferred room count; runs are penalized by their distance from it
+    #[serde(skip_serializing_if = "Option::is_none", rename = "targetRoomCount")]
+    pub target_room_count: Option<f64>,
+    /// Weight of the reachable-area fraction (connectivity) in the score
+    #[serde(default = "default_connectivity_weight", rename = "connectivityWeight")]
+    pub connectivity_weight: f64,
+}
+
+fn default_connectivity_weight() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -142,6 +193,42 @@ pub struct SimulationResults {
     pub constraint_results: HashMap<String, ConstraintStats>,
     #[serde(default)]
     pub warnings: Vec<String>,
+    /// Counts of `from_type -> to_type` room transitions along connections, across all runs
+    #[serde(default, rename = "transitionMatrix")]
+    pub transition_matrix: HashMap<String, HashMap<String, u32>>,
+}
+
+/// Payload of the `simulation-progress` event, sent after each chunk of runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationProgress {
+    pub completed: u32,
+    pub total: u32,
+    #[serde(rename = "elapsedMs")]
+    pub elapsed_ms: u64,
+}
+
+/// How often each room-type pair is connected across a simulation, against chance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjacencyReport {
+    pub runs: u32,
+    /// Connections counted across all runs
+    #[serde(rename = "totalTransitions")]
+    pub total_transitions: u32,
+    /// Sorted by `from_type`, then `to_type`
+    pub pairs: Vec<AdjacencyPair>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjacencyPair {
+    #[serde(rename = "fromType")]
+    pub from_type: String,
+    #[serde(rename = "toType")]
+    pub to_type: String,
+    pub count: u32,
+    /// Share of all transitions that go `from_type -> to_type`
+    pub frequency: f64,
+    /// `frequency` over what independent types would give; above 1 means the pair is favoured
+    pub lift: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -188,3 +275,86 @@ pub struct ConstraintStats {
     pub pass_rate: f64,
     pub violations: u32,
 }
+
+/// Describes the shape of what `generate_once` returns for a generator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputDescriptor {
+    #[serde(rename = "resultType")]
+    pub result_type: String,
+    /// Whether the output carries spatial geometry (rooms, connections)
+    pub spatial: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<crate::models::generator::OutputSchema>,
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+/// Relative weight of each factor in `difficulty_rating`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DifficultyWeights {
+    #[serde(default = "default_difficulty_weight", rename = "enemyCount")]
+    pub enemy_count: f64,
+    #[serde(default = "default_difficulty_weight", rename = "encounterDensity")]
+    pub encounter_density: f64,
+    #[serde(default = "default_difficulty_weight", rename = "pathLength")]
+    pub path_length: f64,
+    #[serde(default = "default_branching_weight")]
+    pub branching: f64,
+}
+
+impl Default for DifficultyWeights {
+    fn default() -> Self {
+        Self {
+            enemy_count: default_difficulty_weight(),
+            encounter_density: default_difficulty_weight(),
+            path_length: default_difficulty_weight(),
+            branching: default_branching_weight(),
+        }
+    }
+}
+
+fn default_difficulty_weight() -> f64 {
+    1.0
+}
+
+fn default_branching_weight() -> f64 {
+    0.5
+}
+
+/// Layout scaled down to fit a square minimap, with its origin at (0, 0)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimapData {
+    pub width: f64,
+    pub height: f64,
+    pub rooms: Vec<MinimapRoom>,
+    pub connections: Vec<MinimapSegment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimapRoom {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub room_type: String,
+    pub bounds: Rectangle,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimapSegment {
+    pub from: LayoutPosition,
+    pub to: LayoutPosition,
+}
+
+/// Lightweight preview of one generation run, used by the seed gallery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationSummary {
+    pub seed: u64,
+    pub success: bool,
+    #[serde(rename = "roomCount")]
+    pub room_count: usize,
+    /// Bounding box around all rooms (absent when the layout has none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<Rectangle>,
+    /// Row-major grid of room coverage per cell over `bounds`, each in 0..1
+    pub density: Vec<Vec<f64>>,
+}
@@ -71,6 +71,7 @@ export interface PortRef {
 export interface EdgeMetadata {
   label?: string;
   animated?: boolean;
+  weight?: number;
 }
 
 export interface Edge {
@@ -239,6 +240,7 @@ export interface SimulationConfig {
   runCount: number;
   seedStart?: number;
   parameters?: Record<string, unknown>;
+  generator?: Generator;
 }
 
 export interface DistributionStats {